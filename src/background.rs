@@ -19,16 +19,8 @@ pub struct BackgroundParticle {
 #[allow(dead_code)]
 impl BackgroundParticle {
     pub fn new() -> Self {
-        use crate::bidule::BiduleType;
-        let kinds = [
-            BiduleType::I,
-            BiduleType::O,
-            BiduleType::T,
-            BiduleType::S,
-            BiduleType::Z,
-            BiduleType::J,
-            BiduleType::L,
-        ];
+        use crate::bidule::PieceSet;
+        let kinds = PieceSet::Tetromino.kinds();
         let kind = kinds[fastrand::usize(..kinds.len())];
 
         // Random slightly muted color
@@ -43,7 +35,7 @@ impl BackgroundParticle {
             x: fastrand::f32() * screen_width(),
             y: screen_height() + fastrand::f32() * 100.0, // Start below screen
             size: 20.0 + fastrand::f32() * 40.0,
-            rotation: fastrand::f32() * 6.28,
+            rotation: fastrand::f32() * std::f32::consts::TAU,
             rot_speed: (fastrand::f32() - 0.5) * 2.0,
             speed: 0.5 + fastrand::f32() * 1.5,
             kind,
@@ -79,15 +71,16 @@ pub fn draw_particle_theme(particles: &[BackgroundParticle]) {
     // 2. Floating Particles
     for p in particles {
         use crate::bidule::Bidule;
-        let bidule = Bidule::new(p.kind);
+        let positions = Bidule::shape(p.kind, 0, 1);
         // Center of the piece
-        let cx: f32 = bidule.positions.iter().map(|pos| pos.x as f32).sum::<f32>() / 4.0;
-        let cy: f32 = bidule.positions.iter().map(|pos| pos.y as f32).sum::<f32>() / 4.0;
+        let count = positions.len() as f32;
+        let cx: f32 = positions.iter().map(|pos| pos.x as f32).sum::<f32>() / count;
+        let cy: f32 = positions.iter().map(|pos| pos.y as f32).sum::<f32>() / count;
 
         let cos_a = p.rotation.cos();
         let sin_a = p.rotation.sin();
 
-        for pos in &bidule.positions {
+        for pos in &positions {
             // Position relative to center
             let rx = pos.x as f32 - cx;
             let ry = pos.y as f32 - cy;
@@ -179,32 +172,19 @@ impl NatureBackground {
             river_ripples.push(Ripple::new());
         }
 
-        let mut trees = Vec::new();
         // Trees placement - Procedural but somewhat deterministic for nice composition
-        // Far hills
-        trees.push(Tree::new(150.0, screen_height() - 250.0, 0.6));
-        trees.push(Tree::new(300.0, screen_height() - 280.0, 0.5));
-        trees.push(Tree::new(
-            screen_width() - 200.0,
-            screen_height() - 260.0,
-            0.6,
-        ));
-
-        // Mid hills
-        trees.push(Tree::new(80.0, screen_height() - 150.0, 0.9));
-        trees.push(Tree::new(
-            screen_width() - 100.0,
-            screen_height() - 180.0,
-            0.8,
-        ));
-
-        // Foreground (large)
-        trees.push(Tree::new(50.0, screen_height() - 50.0, 1.3));
-        trees.push(Tree::new(
-            screen_width() - 60.0,
-            screen_height() - 60.0,
-            1.2,
-        ));
+        let trees = vec![
+            // Far hills
+            Tree::new(150.0, screen_height() - 250.0, 0.6),
+            Tree::new(300.0, screen_height() - 280.0, 0.5),
+            Tree::new(screen_width() - 200.0, screen_height() - 260.0, 0.6),
+            // Mid hills
+            Tree::new(80.0, screen_height() - 150.0, 0.9),
+            Tree::new(screen_width() - 100.0, screen_height() - 180.0, 0.8),
+            // Foreground (large)
+            Tree::new(50.0, screen_height() - 50.0, 1.3),
+            Tree::new(screen_width() - 60.0, screen_height() - 60.0, 1.2),
+        ];
 
         Self {
             clouds,
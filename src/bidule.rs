@@ -1,15 +1,124 @@
 use crate::constants::*;
+use crate::srs::KickTable;
 use macroquad::prelude::*;
 
+/// A piece kind: an index into the piece definitions. The tetrominoes keep their letters
+/// as constants so the rest of the game can name them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BiduleType(usize);
+
+/// Shape, color and rotation data of one piece kind.
+struct PieceDef {
+    /// Spawn orientation (state 0) as (x, y) inside the box, y pointing down
+    cells: &'static [(i32, i32)],
+    /// Side of the square box the piece rotates in
+    box_size: i32,
+    color: Color,
+    kicks: KickTable,
+}
+
+const fn piece(cells: &'static [(i32, i32)], box_size: i32, color: Color, kicks: KickTable) -> PieceDef {
+    PieceDef {
+        cells,
+        box_size,
+        color,
+        kicks,
+    }
+}
+
+/// The 7 tetrominoes, then the 18 one-sided pentominoes (mirror images count as their own piece).
+/// Spawn shapes lie flat side down in the top rows of their box, as in the guideline.
+const PIECES: [PieceDef; 25] = [
+    // Tetrominoes: I O T S Z J L
+    piece(&[(0, 1), (1, 1), (2, 1), (3, 1)], 4, COLOR_I, KickTable::Long),
+    piece(&[(1, 0), (2, 0), (1, 1), (2, 1)], 4, COLOR_O, KickTable::Fixed),
+    piece(&[(1, 0), (0, 1), (1, 1), (2, 1)], 3, COLOR_T, KickTable::Standard),
+    piece(&[(1, 0), (2, 0), (0, 1), (1, 1)], 3, COLOR_S, KickTable::Standard),
+    piece(&[(0, 0), (1, 0), (1, 1), (2, 1)], 3, COLOR_Z, KickTable::Standard),
+    piece(&[(0, 0), (0, 1), (1, 1), (2, 1)], 3, COLOR_J, KickTable::Standard),
+    piece(&[(2, 0), (0, 1), (1, 1), (2, 1)], 3, COLOR_L, KickTable::Standard),
+    // Pentominoes
+    piece(&[(0, 1), (1, 1), (2, 1), (3, 1), (4, 1)], 5, PENTOMINO_COLORS[0], KickTable::Long), // I
+    piece(&[(3, 0), (0, 1), (1, 1), (2, 1), (3, 1)], 4, PENTOMINO_COLORS[1], KickTable::Standard), // L
+    piece(&[(0, 0), (0, 1), (1, 1), (2, 1), (3, 1)], 4, PENTOMINO_COLORS[2], KickTable::Standard), // J
+    piece(&[(2, 0), (3, 0), (0, 1), (1, 1), (2, 1)], 4, PENTOMINO_COLORS[3], KickTable::Standard), // N
+    piece(&[(0, 0), (1, 0), (1, 1), (2, 1), (3, 1)], 4, PENTOMINO_COLORS[4], KickTable::Standard), // N'
+    piece(&[(2, 0), (0, 1), (1, 1), (2, 1), (3, 1)], 4, PENTOMINO_COLORS[5], KickTable::Standard), // Y
+    piece(&[(1, 0), (0, 1), (1, 1), (2, 1), (3, 1)], 4, PENTOMINO_COLORS[6], KickTable::Standard), // Y'
+    piece(&[(0, 0), (1, 0), (0, 1), (1, 1), (2, 1)], 3, PENTOMINO_COLORS[7], KickTable::Standard), // P
+    piece(&[(1, 0), (2, 0), (0, 1), (1, 1), (2, 1)], 3, PENTOMINO_COLORS[8], KickTable::Standard), // P'
+    piece(&[(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)], 3, PENTOMINO_COLORS[9], KickTable::Standard), // U
+    piece(&[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)], 3, PENTOMINO_COLORS[10], KickTable::Standard), // F
+    piece(&[(0, 0), (1, 0), (1, 1), (2, 1), (1, 2)], 3, PENTOMINO_COLORS[11], KickTable::Standard), // F'
+    piece(&[(0, 0), (1, 0), (2, 0), (1, 1), (1, 2)], 3, PENTOMINO_COLORS[12], KickTable::Standard), // T
+    piece(&[(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)], 3, PENTOMINO_COLORS[13], KickTable::Standard), // V
+    piece(&[(0, 0), (0, 1), (1, 1), (1, 2), (2, 2)], 3, PENTOMINO_COLORS[14], KickTable::Standard), // W
+    piece(&[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)], 3, PENTOMINO_COLORS[15], KickTable::Standard), // X
+    piece(&[(0, 0), (1, 0), (1, 1), (1, 2), (2, 2)], 3, PENTOMINO_COLORS[16], KickTable::Standard), // Z
+    piece(&[(1, 0), (2, 0), (1, 1), (1, 2), (0, 2)], 3, PENTOMINO_COLORS[17], KickTable::Standard), // S
+];
+
+/// The pieces a game draws from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PieceSet {
+    Tetromino,
+    Pentomino,
+}
+
+impl PieceSet {
+    pub fn kinds(self) -> &'static [BiduleType] {
+        const TETROMINOES: [BiduleType; 7] = [
+            BiduleType::I,
+            BiduleType::O,
+            BiduleType::T,
+            BiduleType::S,
+            BiduleType::Z,
+            BiduleType::J,
+            BiduleType::L,
+        ];
+        const PENTOMINOES: [BiduleType; 18] = {
+            let mut kinds = [BiduleType(0); 18];
+            let mut i = 0;
+            while i < kinds.len() {
+                kinds[i] = BiduleType(TETROMINO_COUNT + i);
+                i += 1;
+            }
+            kinds
+        };
+        match self {
+            PieceSet::Tetromino => &TETROMINOES,
+            PieceSet::Pentomino => &PENTOMINOES,
+        }
+    }
+
+    /// Columns added to the chosen board so the wider pieces have room to move.
+    pub fn extra_columns(self) -> usize {
+        match self {
+            PieceSet::Tetromino => 0,
+            PieceSet::Pentomino => 2,
+        }
+    }
+}
+
+const TETROMINO_COUNT: usize = 7;
+
+/// Direction of a rotation input.
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub enum BiduleType {
-    I,
-    O,
-    T,
-    S,
-    Z,
-    J,
-    L,
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotation {
+    /// Number of clockwise quarter turns this rotation amounts to.
+    pub fn quarter_turns(self) -> usize {
+        match self {
+            Rotation::Clockwise => 1,
+            Rotation::Half => 2,
+            Rotation::CounterClockwise => 3,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -21,54 +130,70 @@ pub struct Point {
 #[derive(Clone, Debug)]
 pub struct Bidule {
     pub kind: BiduleType,
-    pub positions: [Point; 4],
+    pub positions: Vec<Point>,
     pub color: Color,
     pub rotation_state: usize,
+    /// Cells per mino side (2 in Big mode)
+    pub scale: i32,
     pub pos: Point,
-    pub seeds: [usize; 4],
+    /// One jelly bubble seed per block
+    pub seeds: Vec<usize>,
+}
+
+impl BiduleType {
+    pub const I: BiduleType = BiduleType(0);
+    pub const O: BiduleType = BiduleType(1);
+    pub const T: BiduleType = BiduleType(2);
+    pub const S: BiduleType = BiduleType(3);
+    pub const Z: BiduleType = BiduleType(4);
+    pub const J: BiduleType = BiduleType(5);
+    pub const L: BiduleType = BiduleType(6);
+
+    fn def(self) -> &'static PieceDef {
+        &PIECES[self.0]
+    }
+
+    /// Side of the square bounding box the piece rotates in (SRS pivots).
+    pub fn box_size(self) -> i32 {
+        self.def().box_size
+    }
+
+    pub fn color(self) -> Color {
+        self.def().color
+    }
+
+    /// Which wall kick tests the piece uses.
+    pub fn kicks(self) -> KickTable {
+        self.def().kicks
+    }
+
+    /// Parses a tetromino letter (`I`, `O`, `T`, `S`, `Z`, `J`, `L`), any case.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'I' => Some(BiduleType::I),
+            'O' => Some(BiduleType::O),
+            'T' => Some(BiduleType::T),
+            'S' => Some(BiduleType::S),
+            'Z' => Some(BiduleType::Z),
+            'J' => Some(BiduleType::J),
+            'L' => Some(BiduleType::L),
+            _ => None,
+        }
+    }
 }
 
 impl Bidule {
-    pub fn new(kind: BiduleType) -> Self {
-        let (positions, color) = match kind {
-            // ... (omitted match arms for brevity, logic remains same)
-            BiduleType::I => (
-                [Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }, Point { x: 3, y: 1 }],
-                COLOR_I,
-            ),
-            BiduleType::O => (
-                [Point { x: 1, y: 0 }, Point { x: 2, y: 0 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }],
-                COLOR_O,
-            ),
-            BiduleType::T => (
-                [Point { x: 1, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }],
-                COLOR_T,
-            ),
-            BiduleType::S => (
-                [Point { x: 1, y: 0 }, Point { x: 2, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }],
-                COLOR_S,
-            ),
-            BiduleType::Z => (
-                [Point { x: 0, y: 0 }, Point { x: 1, y: 0 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }],
-                COLOR_Z,
-            ),
-            BiduleType::J => (
-                [Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }],
-                COLOR_J,
-            ),
-            BiduleType::L => (
-                [Point { x: 2, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }],
-                COLOR_L,
-            ),
-        };
+    pub fn new(kind: BiduleType, scale: i32, rng: &mut fastrand::Rng) -> Self {
+        let color = kind.color();
+        let positions = Self::shape(kind, 0, scale);
 
-        let mut seeds = [0; 4];
-        for i in 0..4 {
+        let mut seeds = vec![0; positions.len()];
+        for seed in seeds.iter_mut() {
             // Random seeds. Use 0 for "no bubbles" (20% chance?).
-            if fastrand::f32() < 0.2 {
-                seeds[i] = 0;
+            if rng.f32() < 0.2 {
+                *seed = 0;
             } else {
-                seeds[i] = fastrand::usize(1..10000);
+                *seed = rng.usize(1..10000);
             }
         }
 
@@ -77,37 +202,52 @@ impl Bidule {
             positions,
             color,
             rotation_state: 0,
+            scale,
             pos: Point { x: 3, y: 0 },
             seeds,
         }
     }
 
-    pub fn rotate(&mut self) {
-        if self.kind == BiduleType::O {
-            return;
+    /// Back to spawn orientation, keeping its look (used by hold).
+    /// The board decides where it re-enters.
+    pub fn respawned(&self) -> Bidule {
+        Bidule {
+            positions: Self::shape(self.kind, 0, self.scale),
+            rotation_state: 0,
+            ..self.clone()
         }
+    }
 
-        let mut new_positions = self.positions;
+    /// Block offsets of `kind` in the given rotation state (0 = spawn, 1 = R, 2 = 180, 3 = L).
+    /// Every state is the spawn shape turned clockwise around the center of its box,
+    /// which puts the I piece between cells and keeps the O piece still, as in SRS.
+    /// With a `scale` above 1 every mino becomes a `scale` x `scale` square of cells.
+    pub fn shape(kind: BiduleType, rotation_state: usize, scale: i32) -> Vec<Point> {
+        let mut positions: Vec<Point> = kind.def().cells.iter().map(|&(x, y)| Point { x, y }).collect();
 
-        if self.kind == BiduleType::I {
-            // I piece rotation (approximate)
-            for i in 0..4 {
-                let x = self.positions[i].x;
-                let y = self.positions[i].y;
-                new_positions[i].x = 3 - y;
-                new_positions[i].y = x;
-            }
-        } else {
-            // 3x3 rotation
-            for i in 0..4 {
-                let x = self.positions[i].x;
-                let y = self.positions[i].y;
-                new_positions[i].x = 2 - y;
-                new_positions[i].y = x;
+        let n = kind.box_size();
+        if kind.kicks() != KickTable::Fixed {
+            for _ in 0..rotation_state % 4 {
+                for p in positions.iter_mut() {
+                    *p = Point { x: n - 1 - p.y, y: p.x };
+                }
             }
         }
 
-        self.positions = new_positions;
-        self.rotation_state = (self.rotation_state + 1) % 4;
+        positions
+            .iter()
+            .flat_map(|p| {
+                (0..scale * scale).map(move |i| Point {
+                    x: p.x * scale + i % scale,
+                    y: p.y * scale + i / scale,
+                })
+            })
+            .collect()
+    }
+
+    /// Turns the piece in place. Kicks are resolved by the caller (see `srs`).
+    pub fn rotate(&mut self, rotation: Rotation) {
+        self.rotation_state = (self.rotation_state + rotation.quarter_turns()) % 4;
+        self.positions = Self::shape(self.kind, self.rotation_state, self.scale);
     }
 }
@@ -116,12 +116,12 @@ impl Bonus {
         ]
     }
 
-    pub fn get_random_set(count: usize) -> Vec<Bonus> {
+    pub fn get_random_set(count: usize, rng: &mut fastrand::Rng) -> Vec<Bonus> {
         let mut all = Self::get_all();
         // Weights? For now just shuffle.
         // Maybe ensure at least one Common?
         // Let's just shuffle for pure RNG chaos.
-        fastrand::shuffle(&mut all);
+        rng.shuffle(&mut all);
         all.into_iter().take(count).collect()
     }
 }
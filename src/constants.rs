@@ -1,6 +1,7 @@
 use macroquad::prelude::Color;
 
 pub const BLOCK_SIZE: f32 = 35.0;
+// Default board size, see `Rules` for the per-game value
 pub const GRID_WIDTH: usize = 10;
 pub const GRID_HEIGHT: usize = 20;
 
@@ -15,8 +16,6 @@ pub const fn hex_color(r: u8, g: u8, b: u8) -> Color {
 // UI
 pub const COLOR_UI_BG: Color = hex_color(0x00, 0x24, 0x47); // Deep Blue for UI panels
 
-pub const COLOR_TEXT_SHADOW: Color = Color::new(0.0, 0.0, 0.0, 0.5);
-
 // Blocks (Vivid, Juicy colors)
 pub const COLOR_GREEN: Color = hex_color(0x71, 0xF5, 0x5F); // Lime Green
 pub const COLOR_PURPLE: Color = hex_color(0x9D, 0x51, 0xF3); // Rich Purple
@@ -34,6 +33,32 @@ pub const COLOR_J: Color = COLOR_BLUE;
 pub const COLOR_I: Color = COLOR_CYAN;
 pub const COLOR_T: Color = COLOR_PURPLE;
 pub const COLOR_O: Color = COLOR_YELLOW;
+// One color per pentomino, in `bidule::PIECES` order (I L J N N' Y Y' P P' U F F' T V W X Z S)
+pub const PENTOMINO_COLORS: [Color; 18] = [
+    hex_color(0x4D, 0xF0, 0xD0), // Aqua
+    hex_color(0xFF, 0x7A, 0x3D), // Tangerine
+    hex_color(0x3D, 0x6B, 0xFF), // Royal Blue
+    hex_color(0xB8, 0xF2, 0x3A), // Chartreuse
+    hex_color(0xFF, 0x5C, 0x8A), // Watermelon
+    hex_color(0xFF, 0xB8, 0x4D), // Apricot
+    hex_color(0x7A, 0x9C, 0xFF), // Periwinkle
+    hex_color(0xF2, 0x6B, 0xF2), // Orchid
+    hex_color(0xC0, 0x7A, 0xFF), // Lavender
+    hex_color(0xFF, 0xE1, 0x6B), // Butter
+    hex_color(0x3D, 0xD6, 0x8C), // Emerald
+    hex_color(0x2F, 0xB5, 0xB5), // Teal
+    hex_color(0xD9, 0x4D, 0xFF), // Violet
+    hex_color(0xFF, 0x8F, 0xB3), // Rose
+    hex_color(0xA6, 0xE0, 0xFF), // Ice
+    hex_color(0xFF, 0xF0, 0xE0), // Cream
+    hex_color(0xE0, 0x3E, 0x3E), // Crimson
+    hex_color(0x5F, 0xC9, 0x3E), // Leaf
+];
+pub const COLOR_GARBAGE: Color = hex_color(0x8A, 0x8F, 0x98); // Stone Gray
+
+pub const MAX_NEXT_QUEUE: usize = 6; // Longest next queue the HUD can show
+pub const CASCADE_FALL_SPEED: f32 = 25.0; // Rows per second cascading cells slide down
+pub const CASCADE_PAUSE: f32 = 0.2; // Seconds between cascade steps once the cells landed
 
 pub const BLOCK_ROUNDING: f32 = 8.0; // Roundness of blocks
 pub const UI_ROUNDING: f32 = 15.0; // Roundness of UI panels
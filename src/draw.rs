@@ -5,11 +5,9 @@ use crate::ui::*;
 
 use macroquad::prelude::*;
 
-/// Draws a piece centered within a given rectangle
-pub fn draw_preview_piece(x: f32, y: f32, w: f32, h: f32, piece: &Bidule) {
-    let scale = 1.0;
-    let bs = BLOCK_SIZE * scale;
-
+/// Draws `piece` centered in the given box, at `scale` times the normal mino size,
+/// shrunk if needed so long pieces still fit.
+pub fn draw_preview_piece(x: f32, y: f32, w: f32, h: f32, piece: &Bidule, scale: f32) {
     // Calculate Bounding Box
     let positions = &piece.positions;
     if positions.is_empty() {
@@ -21,8 +19,12 @@ pub fn draw_preview_piece(x: f32, y: f32, w: f32, h: f32, piece: &Bidule) {
     let min_y = positions.iter().map(|p| p.y).min().unwrap_or(0);
     let max_y = positions.iter().map(|p| p.y).max().unwrap_or(0);
 
-    let piece_w = (max_x - min_x + 1) as f32 * bs;
-    let piece_h = (max_y - min_y + 1) as f32 * bs;
+    let cols = (max_x - min_x + 1) as f32;
+    let rows = (max_y - min_y + 1) as f32;
+    let bs = (BLOCK_SIZE * scale / piece.scale as f32).min(w * 0.8 / cols).min(h * 0.8 / rows);
+
+    let piece_w = cols * bs;
+    let piece_h = rows * bs;
 
     // Center the piece
     let offset_x = x + w / 2.0 - piece_w / 2.0 - (min_x as f32 * bs);
@@ -72,6 +74,7 @@ pub struct Connectivity {
 }
 
 /// Draws an individual "Jelly" block with connected textures
+/// `color.a` fades the whole block (fading and invisible stacks).
 pub fn draw_jelly_block(
     x: f32,
     y: f32,
@@ -145,7 +148,7 @@ pub fn draw_jelly_block(
         // --- TRAPPED BUBBLES (Organic/Aerated look) ---
         if bubble_seed != 0 {
              let time = get_time() as f32;
-             let bubble_layer_color = Color::new(1.0, 1.0, 1.0, 0.15); // Faint white
+             let bubble_layer_color = Color::new(1.0, 1.0, 1.0, 0.15 * color.a); // Faint white
              
              // Calculate center for safe positioning
              let center_x = cx + current_size / 2.0;
@@ -243,15 +246,15 @@ pub fn draw_jelly_block(
             f32::min(1.0, c.r + 0.2),
             f32::min(1.0, c.g + 0.2),
             f32::min(1.0, c.b + 0.2),
-            0.9,
+            0.9 * c.a,
         )
     });
 
     // --- 2. Body Layer (Translucent Jelly) ---
-    draw_layer(2.0, &|c| Color::new(c.r, c.g, c.b, 0.75));
+    draw_layer(2.0, &|c| Color::new(c.r, c.g, c.b, 0.75 * c.a));
 
     // --- 3. Inner Core (Denser Volume) ---
-    draw_layer(6.0, &|c| Color::new(c.r * 0.8, c.g * 0.8, c.b * 0.8, 0.95));
+    draw_layer(6.0, &|c| Color::new(c.r * 0.8, c.g * 0.8, c.b * 0.8, 0.95 * c.a));
 
     // --- 3.5 Color Bleed Mixing ---
     // [Logic Removed]
@@ -261,7 +264,7 @@ pub fn draw_jelly_block(
     // But for a gummy look, maybe they should just be on the 'ridges'.
     // Let's keep the localized bubble highlights for now.
 
-    let shine_color = Color::new(1.0, 1.0, 1.0, 0.5);
+    let shine_color = Color::new(1.0, 1.0, 1.0, 0.5 * color.a);
     // Main blob highlight (Top Left)
     if neighbors.top.is_none() && neighbors.left.is_none() {
         draw_circle(
@@ -283,7 +286,7 @@ pub fn draw_jelly_block(
     // Secondary "wet" dot (Bottom Right)
     // Only if exposed
     if neighbors.bottom.is_none() && neighbors.right.is_none() {
-        let wet_color = Color::new(1.0, 1.0, 1.0, 0.8);
+        let wet_color = Color::new(1.0, 1.0, 1.0, 0.8 * color.a);
         draw_circle(
             wx + padding + size * 0.75,
             wy + padding + size * 0.75,
@@ -295,7 +298,9 @@ pub fn draw_jelly_block(
 
 // draw_panel moved to ui.rs
 
+use crate::engine::Phase;
 use crate::game::{Game, GameState};
+use crate::grid::Cell;
 
 /// Main drawing function for the game
 pub fn draw_game(game: &Game) {
@@ -307,25 +312,45 @@ pub fn draw_game(game: &Game) {
         GameState::Start => {
             crate::ui::draw_start_screen(game);
         }
-        GameState::Playing | GameState::GameOver | GameState::ChooseBonus => {
+        GameState::PuzzleSelect => {
+            crate::ui::draw_puzzle_select(game);
+        }
+        GameState::Playing
+        | GameState::GameOver
+        | GameState::ChooseBonus
+        | GameState::Results
+        | GameState::Paused => {
             draw_play_scene(game);
             
             if game.state == GameState::ChooseBonus {
                 crate::ui::draw_bonus_selection(game);
             }
-            if game.state == GameState::GameOver {
-                crate::ui::draw_game_over(game);
-            }
         }
     }
 }
 
+// Side column layout around the board
+const SIDE_PANEL_W: f32 = 260.0;
+const PANEL_SPACING: f32 = 80.0;
+
+/// Cell size that fits a `width` x `height` board on screen, never larger than BLOCK_SIZE.
+pub fn board_block_size(width: usize, height: usize) -> f32 {
+    let max_w = screen_width() - 2.0 * (SIDE_PANEL_W + PANEL_SPACING) - 100.0;
+    let max_h = screen_height() - 200.0;
+    BLOCK_SIZE
+        .min(max_w / width as f32)
+        .min(max_h / height as f32)
+}
+
 fn draw_play_scene(game: &Game) {
+    let grid = &game.engine.grid;
+    let bs = board_block_size(grid.width, grid.height);
+
     // Layout Constants
-    let board_w = GRID_WIDTH as f32 * BLOCK_SIZE;
-    let board_h = GRID_HEIGHT as f32 * BLOCK_SIZE;
-    let spacing = 80.0;
-    let side_panel_w = 260.0;
+    let board_w = grid.width as f32 * bs;
+    let board_h = grid.height as f32 * bs;
+    let spacing = PANEL_SPACING;
+    let side_panel_w = SIDE_PANEL_W;
 
     let total_w = side_panel_w + spacing + board_w + spacing + side_panel_w;
     let total_content_h = board_h;
@@ -350,20 +375,29 @@ fn draw_play_scene(game: &Game) {
     // User requested "level window a bit smaller on top"
     let level_panel_h = 100.0;
     let level_color = Color::new(0.0, 0.5, 0.9, 1.0);
+    // Timed modes show the clock here instead of the level
+    let timed = game.engine.rules.mode.is_timed();
     
     draw_panel(
         next_x,
         stats_y,
         side_panel_w,
         level_panel_h,
-        Some("LEVEL"),
+        Some(if timed { "TIME" } else { "LEVEL" }),
         font_ref,
         level_color,
     );
 
     if let Some(f) = font_ref {
         let pulse_scale = 1.0 + (game.ui_pulse * 0.2);
-        let lvl_text = format!("{}", game.level);
+        let lvl_text = if let Some(limit) = game.engine.rules.mode.time_limit() {
+            // Count down to the limit
+            format_time(limit - game.engine.elapsed)
+        } else if timed {
+            format_time(game.engine.elapsed)
+        } else {
+            format!("{}", game.engine.level)
+        };
         
         // Center the level number big
         let dim = measure_text(&lvl_text, Some(f), 60, 1.0);
@@ -382,19 +416,26 @@ fn draw_play_scene(game: &Game) {
     // User requested "winsows need to be spaced on from an other"
     let spacing_vertical = 100.0; // Increased from 50 to 100
     let next_panel_y = stats_y + level_panel_h + spacing_vertical;
-    let next_panel_h = 250.0; 
-    
-    draw_panel(
-        next_x,
-        next_panel_y,
-        side_panel_w,
-        next_panel_h,
-        Some("NEXT"),
-        font_ref,
-        Color::new(0.0, 0.7, 0.3, 1.0),
-    );
-    if let Some(next_piece) = game.next_pieces.first() {
-        draw_preview_piece(next_x, next_panel_y, side_panel_w, next_panel_h, next_piece);
+    // The first piece gets a full slot, the rest of the queue is stacked smaller below it
+    let first_slot_h = 180.0;
+    let slot_h = 90.0;
+    let queue = &game.engine.next_pieces;
+    if !queue.is_empty() {
+        let next_panel_h = first_slot_h + (queue.len() - 1) as f32 * slot_h;
+        draw_panel(
+            next_x,
+            next_panel_y,
+            side_panel_w,
+            next_panel_h,
+            Some("NEXT"),
+            font_ref,
+            Color::new(0.0, 0.7, 0.3, 1.0),
+        );
+        draw_preview_piece(next_x, next_panel_y, side_panel_w, first_slot_h, &queue[0], 1.0);
+        for (i, piece) in queue.iter().enumerate().skip(1) {
+            let slot_y = next_panel_y + first_slot_h + (i - 1) as f32 * slot_h - 20.0;
+            draw_preview_piece(next_x, slot_y, side_panel_w, slot_h, piece, 0.6);
+        }
     }
 
     // --- 3. Hold Panel (Top Right) ---
@@ -408,8 +449,8 @@ fn draw_play_scene(game: &Game) {
         font_ref,
         Color::new(0.7, 0.2, 0.8, 1.0),
     );
-    if let Some(hold_piece) = &game.hold_piece {
-        draw_preview_piece(hold_x, stats_y, side_panel_w, hold_panel_h, hold_piece);
+    if let Some(hold_piece) = &game.engine.hold_piece {
+        draw_preview_piece(hold_x, stats_y, side_panel_w, hold_panel_h, hold_piece, 1.0);
     }
 
     // --- 4. Score Panel (Right, below Hold) ---
@@ -429,8 +470,17 @@ fn draw_play_scene(game: &Game) {
 
     if let Some(f) = font_ref {
         let pulse_scale = 1.0 + (game.ui_pulse * 0.2);
-        let score_text = format!("{}", game.score);
-        let lines_text = format!("LINES: {}", game.lines_cleared_total);
+        let score_text = format!("{}", game.engine.score);
+        let mode = game.engine.rules.mode;
+        let lines_text = if let Some(puzzle) = &game.engine.rules.puzzle {
+            puzzle.goal.describe()
+        } else if mode.dig_rows().is_some() {
+            format!("GARBAGE LEFT: {}", game.engine.grid.garbage_rows())
+        } else if let Some(goal) = mode.line_goal() {
+            format!("LINES: {}/{}", game.engine.lines_cleared_total, goal)
+        } else {
+            format!("LINES: {}", game.engine.lines_cleared_total)
+        };
 
         // Score Big
         let dim = measure_text(&score_text, Some(f), 50, 1.0);
@@ -454,6 +504,73 @@ fn draw_play_scene(game: &Game) {
         });
     }
 
+    // --- 5. Combo Panel (Right, below Score) ---
+    let combo_panel_y = score_panel_y + score_panel_h + spacing_vertical;
+    let combo_panel_h = 100.0;
+    let combo_active = game.engine.combo > 0;
+    let combo_color = if combo_active {
+        ORANGE
+    } else {
+        Color::new(0.4, 0.4, 0.5, 1.0)
+    };
+
+    draw_panel(
+        hold_x,
+        combo_panel_y,
+        side_panel_w,
+        combo_panel_h,
+        Some("COMBO"),
+        font_ref,
+        combo_color,
+    );
+
+    // Meter: one segment per combo step, capped to the panel width
+    let max_segments = 10;
+    let segment_gap = 4.0;
+    let meter_x = hold_x + 20.0;
+    let meter_w = side_panel_w - 40.0;
+    let segment_w = (meter_w - segment_gap * (max_segments - 1) as f32) / max_segments as f32;
+    let filled = game.engine.combo.clamp(0, max_segments);
+    for i in 0..max_segments {
+        let col = if i < filled {
+            combo_color
+        } else {
+            Color::new(1.0, 1.0, 1.0, 0.1)
+        };
+        draw_rounded_rect(
+            meter_x + i as f32 * (segment_w + segment_gap),
+            combo_panel_y + 60.0,
+            segment_w,
+            20.0,
+            4.0,
+            col,
+        );
+    }
+
+    if let Some(f) = font_ref {
+        let combo_text = if combo_active {
+            format!("x{}", game.engine.combo)
+        } else {
+            "-".to_string()
+        };
+        draw_text_ex(&combo_text, meter_x, combo_panel_y + 45.0, TextParams {
+            font: Some(f),
+            font_size: 35,
+            color: WHITE,
+            ..Default::default()
+        });
+
+        if game.engine.back_to_back {
+            let b2b = "B2B";
+            let dim = measure_text(b2b, Some(f), 30, 1.0);
+            draw_text_ex(b2b, meter_x + meter_w - dim.width, combo_panel_y + 45.0, TextParams {
+                font: Some(f),
+                font_size: 30,
+                color: GOLD,
+                ..Default::default()
+            });
+        }
+    }
 
     // 0.5 Music Button (Bottom Left Icon)
     let btn_size = 50.0;
@@ -521,37 +638,70 @@ fn draw_play_scene(game: &Game) {
         grid_border_color,
     );
 
+    // Pending garbage meter, rising along the left edge of the board
+    // Big mode garbage lines are a mino (several rows) tall
+    let scale = game.engine.rules.mode.block_scale() as usize;
+    let pending = (game.engine.pending_garbage_lines() * scale).min(grid.height);
+    if pending > 0 {
+        let meter_h = pending as f32 * bs;
+        draw_rounded_rect(
+            grid_x - 22.0,
+            grid_y + board_h - meter_h,
+            12.0,
+            meter_h,
+            4.0,
+            COLOR_RED,
+        );
+    }
+
     // Grid Lines
-    for x in 1..GRID_WIDTH {
+    for x in 1..grid.width {
         draw_line(
-            grid_x + x as f32 * BLOCK_SIZE,
+            grid_x + x as f32 * bs,
             grid_y,
-            grid_x + x as f32 * BLOCK_SIZE,
+            grid_x + x as f32 * bs,
             grid_y + board_h,
             1.0,
             Color::new(1.0, 1.0, 1.0, 0.1),
         );
     }
-    for y in 1..GRID_HEIGHT {
+    for y in 1..grid.height {
         draw_line(
             grid_x,
-            grid_y + y as f32 * BLOCK_SIZE,
+            grid_y + y as f32 * bs,
             grid_x + board_w,
-            grid_y + y as f32 * BLOCK_SIZE,
+            grid_y + y as f32 * bs,
             1.0,
             Color::new(1.0, 1.0, 1.0, 0.1),
         );
     }
 
-    // Draw Grid Blocks
-    for y in 0..GRID_HEIGHT {
-        for x in 0..GRID_WIDTH {
-            if let Some(cell) = &game.grid.cells[y][x] {
+    // Draw Grid Blocks (the vanish zone above the board stays hidden)
+    let hidden = grid.buffer as i32;
+    // Faded cells all come back once the game is over
+    let reveal_all = matches!(game.engine.phase, Phase::GameOver | Phase::Finished);
+    let cell_alpha = |cell: &Cell| {
+        if reveal_all {
+            1.0
+        } else {
+            cell.alpha(game.engine.elapsed)
+        }
+    };
+    for y in grid.buffer..grid.total_height() {
+        for x in 0..grid.width {
+            if let Some(cell) = &grid.cells[y][x] {
+                let alpha = cell_alpha(cell);
+                if alpha <= 0.0 {
+                    continue;
+                }
                 let check_neighbor = |nx: i32, ny: i32| -> Option<Color> {
-                    if nx < 0 || nx >= GRID_WIDTH as i32 || ny < 0 || ny >= GRID_HEIGHT as i32 {
+                    if nx < 0 || nx >= grid.width as i32 || ny < hidden || ny >= grid.total_height() as i32 {
                         return None;
                     }
-                    game.grid.cells[ny as usize][nx as usize].as_ref().map(|c| c.color)
+                    grid.cells[ny as usize][nx as usize]
+                        .as_ref()
+                        .filter(|c| cell_alpha(c) > 0.0)
+                        .map(|c| c.color)
                 };
 
                 let neighbors = Connectivity {
@@ -561,11 +711,18 @@ fn draw_play_scene(game: &Game) {
                     left: check_neighbor(x as i32 - 1, y as i32),
                 };
 
+                // Cells moved by cascade gravity slide down into place
+                let slide = game
+                    .falling
+                    .iter()
+                    .find(|(p, _)| p.x == x as i32 && p.y == y as i32)
+                    .map_or(0.0, |&(_, rows)| rows);
+
                 draw_jelly_block(
-                    grid_x + x as f32 * BLOCK_SIZE,
-                    grid_y + y as f32 * BLOCK_SIZE,
-                    BLOCK_SIZE,
-                    cell.color,
+                    grid_x + x as f32 * bs,
+                    grid_y + ((y - grid.buffer) as f32 - slide) * bs,
+                    bs,
+                    Color { a: alpha, ..cell.color },
                     neighbors,
                     false,
                     cell.bubble_seed,
@@ -576,7 +733,7 @@ fn draw_play_scene(game: &Game) {
 
     // Helper to get connectivity for active piece
     let get_piece_conn = |pos: crate::bidule::Point,
-                          all: &[crate::bidule::Point; 4],
+                          all: &[crate::bidule::Point],
                           my_color: Color|
      -> Connectivity {
         let mut conn = Connectivity {
@@ -602,42 +759,45 @@ fn draw_play_scene(game: &Game) {
         conn
     };
 
-    // Draw Ghost Piece
-    let ghost = game.get_ghost_position(); // We might need to make get_ghost_position pub
-    for p in game.current_piece.positions.iter() {
-        let x = ghost.x + p.x;
-        let y = ghost.y + p.y;
-        if y >= 0 {
-            let neighbors =
-                get_piece_conn(*p, &game.current_piece.positions, game.current_piece.color);
-            draw_jelly_block(
-                grid_x + x as f32 * BLOCK_SIZE,
-                grid_y + y as f32 * BLOCK_SIZE,
-                BLOCK_SIZE,
-                game.current_piece.color,
-                neighbors,
-                true,
-                0,
-            );
+    // The locked piece is part of the stack until the cascade brings the next one in
+    if game.engine.phase != Phase::Cascading {
+        // Draw Ghost Piece
+        let ghost = game.engine.get_ghost_position();
+        for p in game.engine.current_piece.positions.iter() {
+            let x = ghost.x + p.x;
+            let y = ghost.y + p.y;
+            if y >= hidden {
+                let neighbors =
+                    get_piece_conn(*p, &game.engine.current_piece.positions, game.engine.current_piece.color);
+                draw_jelly_block(
+                    grid_x + x as f32 * bs,
+                    grid_y + (y - hidden) as f32 * bs,
+                    bs,
+                    game.engine.current_piece.color,
+                    neighbors,
+                    true,
+                    0,
+                );
+            }
         }
-    }
 
-    // Draw Current Piece
-    for (i, p) in game.current_piece.positions.iter().enumerate() {
-        let x = game.current_piece.pos.x + p.x;
-        let y = game.current_piece.pos.y + p.y;
-        if y >= 0 {
-            let neighbors =
-                get_piece_conn(*p, &game.current_piece.positions, game.current_piece.color);
-            draw_jelly_block(
-                grid_x + x as f32 * BLOCK_SIZE,
-                grid_y + y as f32 * BLOCK_SIZE,
-                BLOCK_SIZE,
-                game.current_piece.color,
-                neighbors,
-                false,
-                game.current_piece.seeds[i],
-            );
+        // Draw Current Piece
+        for (i, p) in game.engine.current_piece.positions.iter().enumerate() {
+            let x = game.engine.current_piece.pos.x + p.x;
+            let y = game.engine.current_piece.pos.y + p.y;
+            if y >= hidden {
+                let neighbors =
+                    get_piece_conn(*p, &game.engine.current_piece.positions, game.engine.current_piece.color);
+                draw_jelly_block(
+                    grid_x + x as f32 * bs,
+                    grid_y + (y - hidden) as f32 * bs,
+                    bs,
+                    game.engine.current_piece.color,
+                    neighbors,
+                    false,
+                    game.engine.current_piece.seeds[i],
+                );
+            }
         }
     }
 
@@ -656,7 +816,17 @@ fn draw_play_scene(game: &Game) {
 
     // Overlay Game Over
     if game.state == GameState::GameOver {
-        crate::ui::draw_game_over(game);
+        if game.show_breakdown {
+            crate::ui::draw_score_breakdown(game);
+        } else {
+            crate::ui::draw_game_over(game);
+        }
+    }
+    if game.state == GameState::Results {
+        crate::ui::draw_results(game);
+    }
+    if game.state == GameState::Paused {
+        crate::ui::draw_pause(game);
     }
 }
 
@@ -98,7 +98,7 @@ impl Particle {
             life,
             max_life: life,
             size,
-            rotation: fastrand::f32() * 6.28,
+            rotation: fastrand::f32() * std::f32::consts::TAU,
             angular_velocity: -3.0 + fastrand::f32() * 6.0,
             kind,
         }
@@ -0,0 +1,1570 @@
+use crate::bidule::{Bidule, BiduleType, Point, Rotation};
+use crate::bonuses::{ActiveBonus, Bonus, BonusType};
+use crate::constants::*;
+use crate::gravity::GravityCurve;
+use crate::grid::{Grid, REVEAL_SECONDS};
+use crate::mode::GameMode;
+use crate::puzzle::Puzzle;
+use crate::randomizer::{Randomizer, RandomizerKind, Sequence};
+use crate::scoring::{self, ScoreEvent, ScoreModifier, ScoreSource, TSpin};
+use crate::seed::GameSeed;
+use crate::srs;
+
+/// Player intent for a single simulation step.
+/// Plain flags are edge-triggered ("pressed"), `*_held` and `soft_drop` are held.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InputFrame {
+    pub left: bool,
+    pub right: bool,
+    pub left_held: bool,
+    pub right_held: bool,
+    pub rotate_cw: bool,
+    pub rotate_ccw: bool,
+    pub rotate_180: bool,
+    pub soft_drop: bool,
+    pub hard_drop: bool,
+    pub hold: bool,
+}
+
+/// Tunable rules, fixed for the duration of a game.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub mode: GameMode,
+    /// Seconds a grounded piece waits before locking.
+    pub lock_delay: f32,
+    /// How many moves/rotations may restart the lock delay ("move reset").
+    pub max_lock_resets: u32,
+    /// Delayed Auto Shift: seconds a direction is held before it repeats.
+    pub das: f32,
+    /// Auto Repeat Rate: seconds between repeated shifts. 0 slides straight to the wall.
+    pub arr: f32,
+    /// Soft Drop Factor: how many times faster than gravity soft drop falls (>= 1).
+    pub soft_drop_factor: f64,
+    pub randomizer: RandomizerKind,
+    /// Starting board, piece sequence and goal; replaces the randomizer when set.
+    pub puzzle: Option<Puzzle>,
+    pub gravity: GravityCurve,
+    /// Pieces shown in the next queue (0-6).
+    pub next_queue: usize,
+    pub board_width: usize,
+    pub board_height: usize,
+    /// Hidden rows above the visible board where pieces spawn and may lock.
+    pub buffer_rows: usize,
+    /// Chance (0-1) that each garbage row moves its hole. 0 stacks a clean well.
+    pub garbage_messiness: f32,
+    /// Floating groups fall after clears and bonus blasts, and can set off chain clears.
+    pub cascade: bool,
+    /// Seconds between garbage lines rising on their own. 0 turns it off.
+    pub rising_garbage: f32,
+}
+
+impl Rules {
+    /// Whether the start menu choices are the defaults, whatever the mode. Only such
+    /// games go into the personal bests, so times and scores stay comparable.
+    pub fn is_standard(&self) -> bool {
+        let standard = Rules::default();
+        self.board_width == standard.board_width
+            && self.board_height == standard.board_height
+            && self.rising_garbage == standard.rising_garbage
+            && self.gravity == standard.gravity
+            && self.randomizer == standard.randomizer
+            && self.next_queue == standard.next_queue
+            && self.cascade == standard.cascade
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Marathon,
+            lock_delay: 0.5,
+            max_lock_resets: 15,
+            das: 0.167,
+            arr: 0.033,
+            soft_drop_factor: 20.0,
+            randomizer: RandomizerKind::SevenBag,
+            puzzle: None,
+            gravity: GravityCurve::Guideline,
+            next_queue: 3,
+            board_width: GRID_WIDTH,
+            board_height: GRID_HEIGHT,
+            buffer_rows: 20,
+            garbage_messiness: 0.3,
+            cascade: false,
+            rising_garbage: 0.0,
+        }
+    }
+}
+
+/// Where the simulation currently is. The frontend adds its own menus on top.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Playing,
+    /// Cascade gravity is resolving the last lock, one chain step at a time.
+    Cascading,
+    ChooseBonus,
+    GameOver,
+    /// The mode's goal was reached.
+    Finished,
+}
+
+/// Why a game ended, following the guideline top-out rules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TopOut {
+    /// The next piece spawned overlapping the stack.
+    Block,
+    /// A piece locked entirely inside the hidden vanish zone.
+    Lock,
+    /// Incoming garbage pushed the stack past the top of the vanish zone.
+    Garbage,
+}
+
+impl TopOut {
+    pub fn name(self) -> &'static str {
+        match self {
+            TopOut::Block => "BLOCK OUT",
+            TopOut::Lock => "LOCK OUT",
+            TopOut::Garbage => "GARBAGE OUT",
+        }
+    }
+}
+
+/// Everything noteworthy that happened during a `step`.
+/// The frontend turns these into sounds, particles and callouts.
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    Held,
+    PieceLocked {
+        same_color: bool,
+        diff_color: bool,
+    },
+    Exploded {
+        kind: BonusType,
+        origin: Point,
+        cells: Vec<Point>,
+    },
+    LaserFired {
+        columns: Vec<i32>,
+    },
+    Drilled,
+    LinesCleared {
+        rows: Vec<usize>,
+        tspin: TSpin,
+        /// Consecutive clearing locks before this one (0 on the first clear and on chain steps).
+        combo: i32,
+        /// True when this clear continued a back-to-back chain.
+        back_to_back: bool,
+        /// 1 for the lock's first clear, then 2, 3... for each cascade chain step.
+        chain: u32,
+    },
+    /// A T-spin that cleared nothing; clears report their spin in `LinesCleared`.
+    TSpinNoLines {
+        tspin: TSpin,
+        origin: Point,
+    },
+    /// The line clear left the board completely empty.
+    PerfectClear,
+    /// Cascade gravity dropped the floating groups.
+    CascadeFell {
+        /// Where each fallen cell came to rest, and how many rows it fell
+        fallen: Vec<(Point, i32)>,
+    },
+    /// Pending garbage rose into the board.
+    GarbageReceived {
+        lines: usize,
+    },
+    LevelUp,
+    Saved,
+    ToppedOut,
+    /// The mode's goal was reached; see `Phase::Finished`.
+    Finished,
+    /// A fixed piece sequence ran out before the goal was reached.
+    OutOfPieces,
+}
+
+/// A lock being resolved: its own clear, the clears its cascade added so far, and the
+/// time until the next chain step.
+struct Settling {
+    /// Clears so far, the lock's own included
+    clears: u32,
+    /// Lines of the lock's own clear, for puzzle goals
+    lines: usize,
+    tspin: TSpin,
+    /// Some clear of the lock emptied the board
+    perfect_clear: bool,
+    locked_out: bool,
+    timer: f32,
+}
+
+/// Pure game rules: no window, no clock, no keyboard.
+/// Advance it with `step` and react to the returned events.
+pub struct Engine {
+    pub grid: Grid,
+    pub current_piece: Bidule,
+    pub next_pieces: Vec<Bidule>,
+    pub hold_piece: Option<Bidule>,
+    pub can_hold: bool,
+    pub score: i32,
+    /// Every scoring action of the game, in order
+    pub score_log: Vec<ScoreEvent>,
+    pub level: i32,
+    pub lines_cleared_total: i32,
+    /// Seconds of play. Only advances in `step` while playing, so pauses and bonus drafts
+    /// don't count, and it runs on real time: Chill slows gravity, not the clock.
+    pub elapsed: f64,
+    pub pieces_locked: u32,
+    /// `elapsed` at every 10th line
+    pub splits: Vec<f64>,
+    /// -1 when the last lock cleared nothing, then 0, 1, 2... per consecutive clear
+    pub combo: i32,
+    /// Whether the last line clear was difficult (Tetris or T-spin)
+    pub back_to_back: bool,
+    pub phase: Phase,
+    /// Set once the game is over.
+    pub top_out: Option<TopOut>,
+    /// The game ended because a puzzle's pieces ran out.
+    pub out_of_pieces: bool,
+    pub rules: Rules,
+    pub seed: GameSeed,
+    /// The lock being resolved while `Phase::Cascading`.
+    settling: Option<Settling>,
+    /// Incoming garbage batches, oldest first. Line clears cancel them, other locks let them in.
+    pub pending_garbage: Vec<usize>,
+    garbage_hole: usize,
+    garbage_timer: f32,
+    // Rows fallen but not applied yet; can exceed 1 within a frame at high gravity
+    fall_progress: f64,
+    // Cells the current piece was soft dropped, paid out as one event when it locks
+    soft_drop_cells: i32,
+    lock_timer: f32,
+    lock_resets: u32,
+    lowest_y: i32,
+    // T-spin bookkeeping: was the last successful action a rotation, and did it use
+    // the long (±1, ±2) kick, test 5 of a quarter turn
+    last_move_rotation: bool,
+    last_kick_long: bool,
+    shift_dir: i32,
+    das_timer: f32,
+    arr_timer: f32,
+    randomizer: Box<dyn Randomizer>,
+
+    // Bonus System
+    pub bonus_options: Vec<Bonus>,
+    pub active_bonuses: Vec<ActiveBonus>,
+}
+
+impl Engine {
+    pub fn new(seed: GameSeed) -> Self {
+        Self::with_rules(Rules::default(), seed)
+    }
+
+    /// The seed fully determines the piece sequence, bonus offers and bonus rolls.
+    pub fn with_rules(rules: Rules, mut seed: GameSeed) -> Self {
+        let placeholder = Bidule::new(BiduleType::I, 1, &mut seed.cosmetic);
+        let randomizer: Box<dyn Randomizer> = match &rules.puzzle {
+            Some(puzzle) => Box::new(Sequence::new(puzzle.pieces.clone())),
+            None => rules.randomizer.build(rules.mode.piece_set().kinds()),
+        };
+        let mut engine = Self {
+            grid: Grid::new(rules.board_width, rules.board_height, rules.buffer_rows),
+            current_piece: placeholder,
+            next_pieces: Vec::new(),
+            hold_piece: None,
+            can_hold: true,
+            score: 0,
+            score_log: Vec::new(),
+            level: 1,
+            lines_cleared_total: 0,
+            elapsed: 0.0,
+            pieces_locked: 0,
+            splits: Vec::new(),
+            combo: -1,
+            back_to_back: false,
+            phase: Phase::Playing,
+            top_out: None,
+            out_of_pieces: false,
+            rules,
+            seed,
+            settling: None,
+            pending_garbage: Vec::new(),
+            garbage_hole: 0,
+            garbage_timer: 0.0,
+            fall_progress: 0.0,
+            soft_drop_cells: 0,
+            lock_timer: 0.0,
+            lock_resets: 0,
+            lowest_y: 0,
+            last_move_rotation: false,
+            last_kick_long: false,
+            shift_dir: 0,
+            das_timer: 0.0,
+            arr_timer: 0.0,
+            randomizer,
+            bonus_options: Vec::new(),
+            active_bonuses: Vec::new(),
+        };
+
+        engine.garbage_hole = engine.seed.garbage.usize(..engine.garbage_columns());
+        if let Some(rows) = engine.rules.mode.dig_rows() {
+            // Cheese: every row has its hole somewhere else
+            engine.add_garbage(rows.min(engine.grid.height - 1), 1.0);
+        }
+        if let Some(puzzle) = engine.rules.puzzle.take() {
+            engine.grid.load_rows(&puzzle.rows, &mut engine.seed.cosmetic);
+            let scale = engine.block_scale();
+            engine.hold_piece = puzzle.hold.map(|kind| Bidule::new(kind, scale, &mut engine.seed.cosmetic));
+            engine.rules.puzzle = Some(puzzle);
+        }
+        if let Some(first) = engine.get_next_piece() {
+            engine.current_piece = first;
+        }
+        for _ in 0..engine.rules.next_queue.min(MAX_NEXT_QUEUE) {
+            if let Some(p) = engine.get_next_piece() {
+                engine.next_pieces.push(p);
+            }
+        }
+        engine.enter_board();
+
+        engine
+    }
+
+    fn get_next_piece(&mut self) -> Option<Bidule> {
+        let kind = self.randomizer.next(&mut self.seed.pieces)?;
+        let scale = self.block_scale();
+        let mut piece = Bidule::new(kind, scale, &mut self.seed.cosmetic);
+        piece.pos = self.grid.spawn_position(kind.box_size(), scale);
+        Some(piece)
+    }
+
+    /// Takes the front of the next queue and refills its back. With an empty queue
+    /// this is simply the randomizer's next piece. `None` once a puzzle runs out.
+    fn pop_next_piece(&mut self) -> Option<Bidule> {
+        if let Some(p) = self.get_next_piece() {
+            self.next_pieces.push(p);
+        }
+        (!self.next_pieces.is_empty()).then(|| self.next_pieces.remove(0))
+    }
+
+    /// Advances the simulation by `dt` seconds.
+    pub fn step(&mut self, dt: f32, input: &InputFrame) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if !matches!(self.phase, Phase::Playing | Phase::Cascading) {
+            return events;
+        }
+        self.elapsed += dt as f64;
+        if let Some(limit) = self.rules.mode.time_limit() {
+            if self.elapsed >= limit {
+                self.elapsed = limit;
+                self.phase = Phase::Finished;
+                events.push(GameEvent::Finished);
+                return events;
+            }
+        }
+
+        // The cascade holds the next piece back, input included
+        let cascading = self.phase == Phase::Cascading;
+        if cascading {
+            self.advance_cascade(dt, &mut events);
+        } else {
+            self.handle_input(input, &mut events);
+        }
+
+        if self.phase == Phase::Playing && !cascading {
+            self.handle_auto_shift(dt, input);
+
+            let mut speed = self.gravity_speed();
+            // No soft drop points at 20G, where the piece falls on its own anyway
+            let soft_dropping = input.soft_drop && speed.is_finite();
+            if input.soft_drop {
+                speed *= self.rules.soft_drop_factor.max(1.0);
+            }
+
+            // At 20G the progress is infinite and the piece lands this frame
+            if speed.is_infinite() {
+                self.fall_progress = f64::INFINITY;
+            } else {
+                self.fall_progress += dt as f64 * speed;
+            }
+            while self.fall_progress >= 1.0 {
+                self.fall_progress -= 1.0;
+                if !self.try_shift(0, 1) {
+                    self.fall_progress = 0.0;
+                    break;
+                }
+                if soft_dropping {
+                    self.soft_drop_cells += 1;
+                }
+                if self.current_piece.pos.y > self.lowest_y {
+                    // Reaching a new lowest row earns a fresh set of resets
+                    self.lowest_y = self.current_piece.pos.y;
+                    self.lock_resets = 0;
+                    self.lock_timer = 0.0;
+                }
+            }
+
+            if self.is_grounded() {
+                self.lock_timer += dt;
+                let out_of_resets = self.lock_resets >= self.rules.max_lock_resets;
+                if self.lock_timer >= self.rules.lock_delay || out_of_resets {
+                    self.lock_and_spawn(&mut events);
+                }
+            }
+        }
+
+        if self.rules.rising_garbage > 0.0 {
+            self.garbage_timer += dt;
+            if self.garbage_timer >= self.rules.rising_garbage {
+                self.garbage_timer -= self.rules.rising_garbage;
+                self.queue_garbage(1);
+            }
+        }
+
+        // Update active bonuses
+        self.active_bonuses.retain_mut(|b| {
+            b.timer -= dt;
+            b.timer > 0.0
+        });
+
+        events
+    }
+
+    /// Cell rows per second at the current level, after bonuses.
+    fn gravity_speed(&self) -> f64 {
+        // Level-based speed
+        let base_speed = self.rules.gravity.rows_per_second(self.level);
+
+        // CHILL Bonus: 50% slower
+        let mut speed_mod = 1.0;
+        if self.has_bonus(BonusType::Chill) {
+            speed_mod *= 1.5;
+        }
+
+        // TIME ANCHOR: 10% slower per stack
+        let anchors = self.bonus_count(BonusType::TimeAnchor);
+        if anchors > 0 {
+            speed_mod *= 1.0 + (0.1 * anchors as f64);
+        }
+
+        // Big mode falls the same minos per second over twice the cells
+        base_speed * self.block_scale() as f64 / speed_mod
+    }
+
+    fn has_bonus(&self, kind: BonusType) -> bool {
+        self.active_bonuses.iter().any(|b| b.kind == kind)
+    }
+
+    fn bonus_count(&self, kind: BonusType) -> usize {
+        self.active_bonuses.iter().filter(|b| b.kind == kind).count()
+    }
+
+    fn handle_input(&mut self, input: &InputFrame, events: &mut Vec<GameEvent>) {
+        let step = self.block_scale();
+        if input.left {
+            self.try_manipulate(|engine| engine.try_shift(-step, 0));
+        }
+        if input.right {
+            self.try_manipulate(|engine| engine.try_shift(step, 0));
+        }
+
+        if input.rotate_cw {
+            self.try_manipulate(|engine| engine.try_rotate(Rotation::Clockwise));
+        }
+        if input.rotate_ccw {
+            self.try_manipulate(|engine| engine.try_rotate(Rotation::CounterClockwise));
+        }
+        if input.rotate_180 {
+            self.try_manipulate(|engine| engine.try_rotate(Rotation::Half));
+        }
+
+        if input.hold && self.can_hold {
+            // Swap with the held piece, or stash the current one and take the next
+            let incoming = match self.hold_piece.take() {
+                Some(held) => Some(held),
+                None => self.pop_next_piece(),
+            };
+            if let Some(incoming) = incoming {
+                events.push(GameEvent::Held);
+                // Both pieces go back to their spawn orientation and position
+                self.hold_piece = Some(self.current_piece.respawned());
+                self.current_piece = incoming.respawned();
+                self.current_piece.pos = self.grid.spawn_position(incoming.kind.box_size(), self.block_scale());
+                self.can_hold = false;
+                // The swapped in piece spawns like any other, and can block out the same way
+                if self.grid.is_collision(&self.current_piece) {
+                    self.handle_top_out(TopOut::Block, events);
+                } else {
+                    self.enter_board();
+                }
+            }
+        }
+
+        if input.hard_drop && self.phase == Phase::Playing {
+            let ghost = self.get_ghost_position();
+            let cells = (ghost.y - self.current_piece.pos.y) / self.block_scale();
+            self.award(ScoreSource::HardDrop, cells * scoring::HARD_DROP_POINTS, false);
+            if ghost.y > self.current_piece.pos.y {
+                // Falling into place is a move, so an earlier rotation no longer makes a spin
+                self.last_move_rotation = false;
+                self.last_kick_long = false;
+            }
+            self.current_piece.pos = ghost;
+            self.lock_and_spawn(events);
+        }
+    }
+
+    /// Delayed Auto Shift / Auto Repeat Rate for held left/right.
+    /// The first cell of a press is handled by `handle_input`; this only repeats.
+    fn handle_auto_shift(&mut self, dt: f32, input: &InputFrame) {
+        // A fresh press restarts the charge. Otherwise keep the current direction
+        // while it is held, falling back to the other one if it is still down.
+        let dir = if input.left {
+            -1
+        } else if input.right {
+            1
+        } else if (self.shift_dir == -1 && input.left_held) || (self.shift_dir == 1 && input.right_held) {
+            self.shift_dir
+        } else if input.left_held {
+            -1
+        } else if input.right_held {
+            1
+        } else {
+            0
+        };
+
+        if dir != self.shift_dir || input.left || input.right {
+            self.shift_dir = dir;
+            self.das_timer = 0.0;
+            self.arr_timer = 0.0;
+            return;
+        }
+        if dir == 0 {
+            return;
+        }
+
+        let charged = self.das_timer >= self.rules.das;
+        self.das_timer += dt;
+        if self.das_timer < self.rules.das {
+            return;
+        }
+
+        let step = dir * self.block_scale();
+        if self.rules.arr <= 0.0 {
+            self.try_manipulate(|engine| {
+                let mut moved = false;
+                while engine.try_shift(step, 0) {
+                    moved = true;
+                }
+                moved
+            });
+        } else {
+            if charged {
+                self.arr_timer += dt;
+            } else {
+                // The first repeat comes as DAS runs out, the time past it counts toward the next
+                self.arr_timer = self.das_timer - self.rules.das;
+                self.try_manipulate(|engine| engine.try_shift(step, 0));
+            }
+            while self.arr_timer >= self.rules.arr {
+                self.arr_timer -= self.rules.arr;
+                self.try_manipulate(|engine| engine.try_shift(step, 0));
+            }
+        }
+    }
+
+    /// Moves the current piece by one cell offset if it fits.
+    fn try_shift(&mut self, dx: i32, dy: i32) -> bool {
+        let mut moved = self.current_piece.clone();
+        moved.pos.x += dx;
+        moved.pos.y += dy;
+        if self.grid.is_collision(&moved) {
+            return false;
+        }
+        self.current_piece = moved;
+        self.last_move_rotation = false;
+        true
+    }
+
+    /// Runs a player move or rotation. A successful one on the ground restarts
+    /// the lock delay, until the piece runs out of resets.
+    fn try_manipulate(&mut self, action: impl FnOnce(&mut Self) -> bool) {
+        let was_grounded = self.is_grounded();
+        if action(self) && was_grounded && self.lock_resets < self.rules.max_lock_resets {
+            self.lock_resets += 1;
+            self.lock_timer = 0.0;
+        }
+    }
+
+    /// True when the current piece rests on the stack or the floor.
+    pub fn is_grounded(&self) -> bool {
+        let mut below = self.current_piece.clone();
+        below.pos.y += 1;
+        self.grid.is_collision(&below)
+    }
+
+    /// A fresh piece drops one row (of minos) right away if it can, so it shows at the top of the board.
+    fn enter_board(&mut self) {
+        self.try_shift(0, self.block_scale());
+        self.reset_lock_state();
+    }
+
+    fn reset_lock_state(&mut self) {
+        self.lock_timer = 0.0;
+        self.lock_resets = 0;
+        self.lowest_y = self.current_piece.pos.y;
+        self.fall_progress = 0.0;
+        self.last_move_rotation = false;
+    }
+
+    /// Turns the current piece, trying each SRS kick in order.
+    fn try_rotate(&mut self, rotation: Rotation) -> bool {
+        let from = self.current_piece.rotation_state;
+        let mut rotated = self.current_piece.clone();
+        rotated.rotate(rotation);
+
+        let kicks = srs::kicks(rotated.kind, from, rotated.rotation_state);
+        let scale = rotated.scale;
+        for (i, &(dx, dy)) in kicks.iter().enumerate() {
+            let mut candidate = rotated.clone();
+            candidate.pos.x += dx * scale;
+            candidate.pos.y -= dy * scale; // Kick tables are y-up
+            if !self.grid.is_collision(&candidate) {
+                self.current_piece = candidate;
+                self.last_move_rotation = true;
+                // 180° turns use their own table, where test 5 is a plain sideways nudge
+                self.last_kick_long = rotation != Rotation::Half && i == 4;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn get_ghost_position(&self) -> Point {
+        let mut ghost = self.current_piece.clone();
+        while !self.grid.is_collision(&ghost) {
+            ghost.pos.y += 1;
+        }
+        ghost.pos.y -= 1;
+        ghost.pos
+    }
+
+    /// 3-corner rule: a T that rotated into place with 3 of the 4 corners
+    /// around its center blocked. It is a full T-spin when both corners on the
+    /// pointing side are blocked, or when the last kick was the long (1, 2) test of a quarter turn.
+    fn detect_tspin(&self) -> TSpin {
+        let piece = &self.current_piece;
+        if piece.kind != BiduleType::T || !self.last_move_rotation {
+            return TSpin::None;
+        }
+
+        // Corners are whole minos; at a bigger scale, test their top-left cell
+        let s = piece.scale;
+        let blocked = |dx: i32, dy: i32| self.grid.is_blocked(piece.pos.x + (1 + dx) * s, piece.pos.y + (1 + dy) * s);
+
+        let corners = [(-1, -1), (1, -1), (1, 1), (-1, 1)];
+        let filled = corners.iter().filter(|&&(dx, dy)| blocked(dx, dy)).count();
+        if filled < 3 {
+            return TSpin::None;
+        }
+
+        // Corners on the side the T points to, per rotation state (y-down)
+        let front = match piece.rotation_state {
+            0 => [(-1, -1), (1, -1)],
+            1 => [(1, -1), (1, 1)],
+            2 => [(1, 1), (-1, 1)],
+            _ => [(-1, 1), (-1, -1)],
+        };
+        if front.iter().all(|&(dx, dy)| blocked(dx, dy)) || self.last_kick_long {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+
+    /// Clears a 3x3 area around every block of the current piece.
+    fn explode_piece_area(&mut self) -> Vec<Point> {
+        let mut cells = Vec::new();
+        for p in self.current_piece.positions.iter() {
+            let cx = self.current_piece.pos.x + p.x;
+            let cy = self.current_piece.pos.y + p.y;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let nx = cx + dx;
+                    let ny = cy + dy;
+                    if self.grid.in_bounds(nx, ny) {
+                        self.grid.cells[ny as usize][nx as usize] = None;
+                        cells.push(Point { x: nx, y: ny });
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn lock_and_spawn(&mut self, events: &mut Vec<GameEvent>) {
+        // Merge/Lock feedback based on neighbors
+        let mut same_color = false;
+        let mut diff_color = false;
+        let p_color = self.current_piece.color;
+
+        for p in self.current_piece.positions.iter() {
+            let x = self.current_piece.pos.x + p.x;
+            let y = self.current_piece.pos.y + p.y;
+
+            let neighbors = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+            for (dx, dy) in neighbors {
+                let nx = x + dx;
+                let ny = y + dy;
+
+                if self.grid.in_bounds(nx, ny) {
+                    if let Some(cell) = &self.grid.cells[ny as usize][nx as usize] {
+                        if cell.color == p_color {
+                            same_color = true;
+                        } else {
+                            diff_color = true;
+                        }
+                    }
+                }
+            }
+        }
+        events.push(GameEvent::PieceLocked {
+            same_color,
+            diff_color,
+        });
+
+        let tspin = self.detect_tspin();
+
+        let locked_out = self
+            .current_piece
+            .positions
+            .iter()
+            .all(|p| self.grid.is_hidden(self.current_piece.pos.y + p.y));
+        let fade_at = self.rules.mode.stack_fade().map(|delay| self.elapsed + delay);
+        self.grid.lock_piece(&self.current_piece, fade_at);
+        self.pieces_locked += 1;
+
+        // --- ONE-TIME BONUSES (Bomb / Laser) ---
+        let mut bonuses_to_remove = Vec::new();
+        for i in 0..self.active_bonuses.len() {
+            match self.active_bonuses[i].kind {
+                BonusType::Bomb => {
+                    // For each block in the piece, explode radius 1
+                    let cells = self.explode_piece_area();
+                    events.push(GameEvent::Exploded {
+                        kind: BonusType::Bomb,
+                        origin: self.current_piece.pos,
+                        cells,
+                    });
+                    bonuses_to_remove.push(i);
+                }
+                BonusType::VerticalLaser => {
+                    // Clear columns occupied by the piece
+                    let mut columns: Vec<i32> = Vec::new();
+                    for p in self.current_piece.positions.iter() {
+                        let c = self.current_piece.pos.x + p.x;
+                        if c >= 0 && c < self.grid.width as i32 && !columns.contains(&c) {
+                            columns.push(c);
+                        }
+                    }
+                    for &c in &columns {
+                        for row in self.grid.cells.iter_mut() {
+                            row[c as usize] = None;
+                        }
+                    }
+                    events.push(GameEvent::LaserFired { columns });
+                    bonuses_to_remove.push(i);
+                }
+                BonusType::Drill => {
+                    // Clears the columns BELOW the piece positions
+                    for p in self.current_piece.positions.iter() {
+                        let cx = self.current_piece.pos.x + p.x;
+                        let cy = self.current_piece.pos.y + p.y;
+                        if cx >= 0 && cx < self.grid.width as i32 {
+                            for y in cy.max(0)..self.grid.total_height() as i32 {
+                                self.grid.cells[y as usize][cx as usize] = None;
+                            }
+                        }
+                    }
+                    events.push(GameEvent::Drilled);
+                    bonuses_to_remove.push(i);
+                }
+                BonusType::VolatileGrid if self.seed.bonuses.f32() < 0.10 => {
+                    // 10% chance to explode 3x3, same area as the Bomb
+                    let cells = self.explode_piece_area();
+                    events.push(GameEvent::Exploded {
+                        kind: BonusType::VolatileGrid,
+                        origin: self.current_piece.pos,
+                        cells,
+                    });
+                }
+                _ => {}
+            }
+        }
+        for idx in bonuses_to_remove.iter().rev() {
+            self.active_bonuses.remove(*idx);
+        }
+
+        let cleared_rows = self.grid.clear_lines();
+
+        if cleared_rows.is_empty() && tspin != TSpin::None {
+            events.push(GameEvent::TSpinNoLines {
+                tspin,
+                origin: self.current_piece.pos,
+            });
+        }
+
+        let soft_drop = std::mem::take(&mut self.soft_drop_cells) / self.block_scale() * scoring::SOFT_DROP_POINTS;
+        self.award(ScoreSource::SoftDrop, soft_drop, false);
+
+        let mut settling = Settling {
+            clears: 0,
+            lines: cleared_rows.len().div_ceil(self.block_scale() as usize),
+            tspin,
+            perfect_clear: false,
+            locked_out,
+            timer: 0.0,
+        };
+        if cleared_rows.is_empty() {
+            // A T-spin scores even when it clears nothing
+            self.award(ScoreSource::LineClear, scoring::line_clear_points(0, tspin) * self.level, false);
+        } else {
+            self.score_clear(&mut settling, cleared_rows, tspin, events);
+        }
+
+        if self.rules.cascade && self.drop_loose_groups(&mut settling, events) {
+            self.settling = Some(settling);
+            self.phase = Phase::Cascading;
+            return;
+        }
+        self.finish_lock(settling, events);
+    }
+
+    /// Scores one clear of the lock being resolved, and lets its attack cancel incoming
+    /// garbage. The lock's first clear extends the combo, every later one is a chain step.
+    fn score_clear(
+        &mut self,
+        settling: &mut Settling,
+        rows: Vec<usize>,
+        tspin: TSpin,
+        events: &mut Vec<GameEvent>,
+    ) {
+        // In Big mode a line is a row of minos; a half cleared mino row still counts as one
+        let lines = rows.len().div_ceil(self.block_scale() as usize);
+        // Clears score at the level they were made on, before any level up
+        let level = self.level;
+        settling.clears += 1;
+        let chain = settling.clears;
+
+        let points = scoring::line_clear_points(lines, tspin);
+        if chain == 1 {
+            self.award(ScoreSource::LineClear, points * level, true);
+        } else {
+            self.award(ScoreSource::Chain, scoring::chain_points(lines, chain) * level, true);
+        }
+        self.lines_cleared_total += lines as i32;
+        // A faded stack flashes back into view
+        self.grid.reveal(self.elapsed + REVEAL_SECONDS);
+
+        let difficult = scoring::is_difficult(lines, tspin);
+        let back_to_back = difficult && self.back_to_back;
+        if back_to_back {
+            let extra = scoring::back_to_back_points(points) - points;
+            self.award(ScoreSource::BackToBack, extra * level, true);
+        }
+        // Like the combo, the streak follows the lock's first clear; chain steps keep it as is
+        if chain == 1 {
+            self.back_to_back = difficult;
+        }
+
+        // The combo counts clearing locks, so chain steps neither extend nor repeat it
+        let combo = if chain == 1 {
+            self.combo += 1;
+            self.award(ScoreSource::Combo, scoring::combo_points(self.combo) * level, true);
+            self.combo
+        } else {
+            0
+        };
+
+        let perfect_clear = self.grid.is_empty();
+        if perfect_clear {
+            let bonus = scoring::perfect_clear_points(lines, back_to_back);
+            self.award(ScoreSource::PerfectClear, bonus * level, true);
+        }
+        settling.perfect_clear |= perfect_clear;
+
+        events.push(GameEvent::LinesCleared {
+            rows,
+            tspin,
+            combo,
+            back_to_back,
+            chain,
+        });
+        if perfect_clear {
+            events.push(GameEvent::PerfectClear);
+        }
+
+        let attack = scoring::attack_lines(lines, tspin, back_to_back, combo, perfect_clear);
+        self.cancel_garbage(attack);
+
+        self.record_splits();
+    }
+
+    /// Everything after a lock's clears, once the board settled: garbage, goal, level up,
+    /// the next piece and top outs.
+    fn finish_lock(&mut self, settling: Settling, events: &mut Vec<GameEvent>) {
+        let Settling {
+            clears,
+            lines,
+            tspin,
+            perfect_clear,
+            locked_out,
+            ..
+        } = settling;
+
+        let mut garbage_out = false;
+        if clears == 0 {
+            self.combo = -1;
+            garbage_out = self.receive_garbage(events);
+        }
+
+        if self.goal_reached(lines, tspin, perfect_clear) {
+            self.phase = Phase::Finished;
+            events.push(GameEvent::Finished);
+            return;
+        }
+
+        // Level up every 10 lines
+        let new_level = (self.lines_cleared_total / 10) + 1;
+        if new_level > self.level {
+            self.level = new_level;
+            events.push(GameEvent::LevelUp);
+
+            // TRIGGER BONUS SELECTION
+            if self.rules.mode.has_bonus_draft() {
+                self.phase = Phase::ChooseBonus;
+                self.bonus_options = Bonus::get_random_set(3, &mut self.seed.bonuses);
+            }
+        }
+
+        let Some(next) = self.pop_next_piece() else {
+            self.phase = Phase::GameOver;
+            self.out_of_pieces = true;
+            events.push(GameEvent::OutOfPieces);
+            return;
+        };
+        self.current_piece = next;
+        self.can_hold = true;
+
+        let top_out = if locked_out {
+            Some(TopOut::Lock)
+        } else if garbage_out {
+            Some(TopOut::Garbage)
+        } else if self.grid.is_collision(&self.current_piece) {
+            Some(TopOut::Block)
+        } else {
+            None
+        };
+        if top_out.is_none() {
+            self.enter_board();
+        }
+
+        if let Some(cause) = top_out {
+            self.handle_top_out(cause, events);
+        }
+    }
+
+    /// Ends the game by `cause`, unless a Life Insurance clears the board instead.
+    fn handle_top_out(&mut self, cause: TopOut, events: &mut Vec<GameEvent>) {
+        // Life Insurance Check
+        if let Some(pos) = self
+            .active_bonuses
+            .iter()
+            .position(|b| b.kind == BonusType::LifeInsurance)
+        {
+            // Consume Life Insurance
+            self.active_bonuses.remove(pos);
+            self.grid.clear(); // Clear board!
+            self.enter_board();
+            events.push(GameEvent::Saved);
+        } else {
+            self.phase = Phase::GameOver;
+            self.top_out = Some(cause);
+            events.push(GameEvent::ToppedOut);
+        }
+    }
+
+    /// Lets every floating group fall. Returns false if nothing moved, otherwise waits
+    /// for the cells to land before the next chain step.
+    fn drop_loose_groups(&mut self, settling: &mut Settling, events: &mut Vec<GameEvent>) -> bool {
+        let fallen = self.grid.settle();
+        let Some(farthest) = fallen.iter().map(|&(_, rows)| rows).max() else {
+            return false;
+        };
+        settling.timer = farthest as f32 / CASCADE_FALL_SPEED + CASCADE_PAUSE;
+        events.push(GameEvent::CascadeFell { fallen });
+        true
+    }
+
+    /// One chain step once the fallen cells landed: clears the lines they completed and
+    /// drops what that left floating. The next piece comes in when nothing clears.
+    fn advance_cascade(&mut self, dt: f32, events: &mut Vec<GameEvent>) {
+        let Some(mut settling) = self.settling.take() else {
+            self.phase = Phase::Playing;
+            return;
+        };
+        settling.timer -= dt;
+        if settling.timer > 0.0 {
+            self.settling = Some(settling);
+            return;
+        }
+
+        let rows = self.grid.clear_lines();
+        if !rows.is_empty() {
+            self.score_clear(&mut settling, rows, TSpin::None, events);
+            if self.drop_loose_groups(&mut settling, events) {
+                self.settling = Some(settling);
+                return;
+            }
+        }
+        self.phase = Phase::Playing;
+        self.finish_lock(settling, events);
+    }
+
+    /// Notes the time of every 10th line reached so far.
+    fn record_splits(&mut self) {
+        while self.splits.len() < (self.lines_cleared_total / 10) as usize {
+            self.splits.push(self.elapsed);
+        }
+    }
+
+    /// Scores one action: active bonuses scale its points, then it is added and logged.
+    /// `cleared` is true for points earned by clearing lines.
+    fn award(&mut self, source: ScoreSource, base: i32, cleared: bool) {
+        if base <= 0 {
+            return;
+        }
+        let event = ScoreEvent::new(source, base, self.score_modifiers(cleared), self.elapsed);
+        self.score += event.gain;
+        self.score_log.push(event);
+    }
+
+    /// The multipliers active bonuses put on points, given whether they came from clearing lines.
+    fn score_modifiers(&self, cleared: bool) -> Vec<ScoreModifier> {
+        let mut modifiers = Vec::new();
+
+        // Double Score
+        if self.has_bonus(BonusType::ScoreMultiplier) {
+            modifiers.push(ScoreModifier {
+                name: "DOUBLE SCORE",
+                factor: 2.0,
+            });
+        }
+
+        // Golden Pickaxe: +20% per stack, on lines only
+        let pickaxes = self.bonus_count(BonusType::GoldenPickaxe);
+        if pickaxes > 0 && cleared {
+            modifiers.push(ScoreModifier {
+                name: "GOLD PICKAXE",
+                factor: 1.0 + 0.2 * pickaxes as f32,
+            });
+        }
+
+        modifiers
+    }
+
+    /// Adds a batch of garbage lines to the incoming queue.
+    pub fn queue_garbage(&mut self, lines: usize) {
+        if lines > 0 {
+            self.pending_garbage.push(lines);
+        }
+    }
+
+    /// Total garbage lines waiting to rise.
+    pub fn pending_garbage_lines(&self) -> usize {
+        self.pending_garbage.iter().sum()
+    }
+
+    /// Spends `attack` lines against the incoming queue, oldest batch first.
+    fn cancel_garbage(&mut self, mut attack: usize) {
+        while attack > 0 && !self.pending_garbage.is_empty() {
+            let cancelled = attack.min(self.pending_garbage[0]);
+            self.pending_garbage[0] -= cancelled;
+            attack -= cancelled;
+            if self.pending_garbage[0] == 0 {
+                self.pending_garbage.remove(0);
+            }
+        }
+    }
+
+    /// Raises every pending garbage line into the board. Returns true on a garbage out.
+    fn receive_garbage(&mut self, events: &mut Vec<GameEvent>) -> bool {
+        let lines = self.pending_garbage_lines();
+        if lines == 0 {
+            return false;
+        }
+        self.pending_garbage.clear();
+
+        let overflow = self.add_garbage(lines, self.rules.garbage_messiness);
+        events.push(GameEvent::GarbageReceived { lines });
+        overflow
+    }
+
+    /// Pushes `lines` garbage rows in from the bottom; `messiness` is the chance each row
+    /// moves the hole. Returns true if the stack was pushed off the top.
+    /// In Big mode every line is a mino tall and its hole a mino wide.
+    fn add_garbage(&mut self, lines: usize, messiness: f32) -> bool {
+        let scale = self.block_scale() as usize;
+        let columns = self.garbage_columns();
+        let mut overflow = false;
+        for _ in 0..lines {
+            if columns > 1 && self.seed.garbage.f32() < messiness {
+                // Move the hole to a different column
+                let shift = self.seed.garbage.usize(1..columns);
+                self.garbage_hole = (self.garbage_hole + shift) % columns;
+            }
+            let hole = self.garbage_hole * scale..(self.garbage_hole + 1) * scale;
+            for _ in 0..scale {
+                overflow |= self.grid.add_garbage_row(hole.clone(), &mut self.seed.cosmetic);
+            }
+        }
+        overflow
+    }
+
+    /// Columns of minos a garbage hole can sit in.
+    fn garbage_columns(&self) -> usize {
+        self.grid.width / self.block_scale() as usize
+    }
+
+    /// Cells per mino side, see `GameMode::block_scale`.
+    fn block_scale(&self) -> i32 {
+        self.rules.mode.block_scale()
+    }
+
+    /// Whether the mode's win condition is met after a lock that cleared `lines`:
+    /// the puzzle goal, the line goal, or a dug-out board.
+    fn goal_reached(&self, lines: usize, tspin: TSpin, perfect_clear: bool) -> bool {
+        if let Some(puzzle) = &self.rules.puzzle {
+            return puzzle.goal.is_met(
+                self.lines_cleared_total,
+                self.pieces_locked,
+                lines,
+                tspin,
+                perfect_clear,
+            );
+        }
+        let lines_done = self
+            .rules
+            .mode
+            .line_goal()
+            .is_some_and(|goal| self.lines_cleared_total >= goal);
+        let dug_out = self.rules.mode.dig_rows().is_some() && self.grid.garbage_rows() == 0;
+        lines_done || dug_out
+    }
+
+    /// Resolves a pending bonus draft. Returns the chosen bonus, if any.
+    pub fn choose_bonus(&mut self, index: usize) -> Option<Bonus> {
+        if self.phase != Phase::ChooseBonus {
+            return None;
+        }
+        let bonus = self.bonus_options.get(index).cloned();
+        if let Some(bonus) = &bonus {
+            self.activate_bonus(bonus.kind);
+        }
+        self.bonus_options.clear();
+        self.phase = Phase::Playing;
+        bonus
+    }
+
+    pub fn activate_bonus(&mut self, kind: BonusType) {
+        // Some bonuses might have immediate effects, others are stored
+        let duration = match kind {
+            BonusType::Chill => 60.0,           // 60 seconds
+            BonusType::ScoreMultiplier => 60.0, // Lasts for 60s
+            // Relics are Infinite
+            BonusType::TimeAnchor
+            | BonusType::GoldenPickaxe
+            | BonusType::VolatileGrid
+            | BonusType::LifeInsurance => 999999.0,
+            // Others are "One Time Use" on next lock
+            _ => 9999.0, // Until used
+        };
+
+        self.active_bonuses.push(ActiveBonus {
+            kind,
+            timer: duration,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Cell;
+    use macroquad::prelude::GRAY;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn new_engine() -> Engine {
+        Engine::new(GameSeed::new(1))
+    }
+
+    fn press(engine: &mut Engine, input: InputFrame) -> Vec<GameEvent> {
+        engine.step(FRAME, &input)
+    }
+
+    fn block() -> Cell {
+        Cell {
+            color: GRAY,
+            bubble_seed: 0,
+            garbage: false,
+            fade_at: None,
+        }
+    }
+
+    /// Fills the bottom of the board with `rows`, top to bottom; `#` is a block.
+    fn fill(engine: &mut Engine, rows: &[&str]) {
+        let top = engine.grid.total_height() - rows.len();
+        for (i, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    engine.grid.cells[top + i][x] = Some(block());
+                }
+            }
+        }
+    }
+
+    /// Puts a fresh `kind` in play with its box at (`x`, `y`), in the spawn orientation.
+    fn place(engine: &mut Engine, kind: BiduleType, x: i32, y: i32) {
+        engine.current_piece = Bidule::new(kind, 1, &mut engine.seed.cosmetic);
+        engine.current_piece.pos = Point { x, y };
+        engine.reset_lock_state();
+    }
+
+    /// The bottom row of the board.
+    fn floor(engine: &Engine) -> i32 {
+        engine.grid.total_height() as i32 - 1
+    }
+
+    fn locked(events: &[GameEvent]) -> bool {
+        events.iter().any(|e| matches!(e, GameEvent::PieceLocked { .. }))
+    }
+
+    fn blocks(engine: &Engine) -> usize {
+        engine.grid.cells.iter().flatten().filter(|c| c.is_some()).count()
+    }
+
+    #[test]
+    fn hard_drop_locks_and_brings_in_the_next_piece() {
+        let mut engine = new_engine();
+        let next = engine.next_pieces[0].kind;
+        let events = press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+
+        assert!(events.iter().any(|e| matches!(e, GameEvent::PieceLocked { .. })));
+        assert_eq!(blocks(&engine), 4);
+        assert_eq!(engine.current_piece.kind, next);
+    }
+
+    #[test]
+    fn completed_row_is_cleared() {
+        let mut engine = new_engine();
+        fill(&mut engine, &["###....###"]);
+        place(&mut engine, BiduleType::I, 3, 0);
+        let events = press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+
+        assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared { .. })));
+        assert_eq!(engine.lines_cleared_total, 1);
+        assert_eq!(blocks(&engine), 0);
+    }
+
+    #[test]
+    fn i_kicks_off_the_right_wall() {
+        let mut engine = new_engine();
+        place(&mut engine, BiduleType::I, 7, 0);
+        engine.current_piece.rotate(Rotation::Clockwise);
+        // Upright against the wall, lying down needs one column more than is left
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+
+        assert_eq!(engine.current_piece.rotation_state, 2);
+        assert_eq!((engine.current_piece.pos.x, engine.current_piece.pos.y), (6, 0));
+    }
+
+    #[test]
+    fn t_floor_kicks_out_of_a_slot() {
+        let mut engine = new_engine();
+        fill(&mut engine, &["###...####"]);
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::T, 3, floor - 1);
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+
+        assert_eq!(engine.current_piece.rotation_state, 1);
+        assert_eq!((engine.current_piece.pos.x, engine.current_piece.pos.y), (2, floor - 2));
+    }
+
+    #[test]
+    fn i_counter_clockwise_kicks_with_the_i_table() {
+        let mut engine = new_engine();
+        place(&mut engine, BiduleType::I, 7, 0);
+        engine.current_piece.rotate(Rotation::Clockwise);
+        // R->0 for the I tries (+2, 0) before (-1, 0); every JLSTZ test would fail here
+        press(&mut engine, InputFrame { rotate_ccw: true, ..Default::default() });
+
+        assert_eq!(engine.current_piece.rotation_state, 0);
+        assert_eq!((engine.current_piece.pos.x, engine.current_piece.pos.y), (6, 0));
+    }
+
+    #[test]
+    fn half_turn_kicks_up_off_the_floor() {
+        let mut engine = new_engine();
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::T, 3, floor - 1);
+        // Pointing down, the T needs the row below its flat side, which is the floor
+        press(&mut engine, InputFrame { rotate_180: true, ..Default::default() });
+
+        assert_eq!(engine.current_piece.rotation_state, 2);
+        assert_eq!((engine.current_piece.pos.x, engine.current_piece.pos.y), (3, floor - 2));
+    }
+
+    #[test]
+    fn grounded_piece_locks_after_the_lock_delay() {
+        let mut engine = new_engine();
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::O, 3, floor - 1);
+        let frames = (engine.rules.lock_delay / FRAME) as usize;
+        for _ in 1..frames {
+            assert!(!locked(&press(&mut engine, InputFrame::default())));
+        }
+        assert!((0..2).any(|_| locked(&press(&mut engine, InputFrame::default()))));
+    }
+
+    #[test]
+    fn moves_and_rotations_reset_the_lock_delay_up_to_the_cap() {
+        let mut engine = new_engine();
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::O, 3, floor - 1);
+        let moves = [
+            InputFrame { left: true, ..Default::default() },
+            InputFrame { right: true, ..Default::default() },
+            InputFrame { rotate_cw: true, ..Default::default() },
+        ];
+        // Each reset comes just before the delay would run out
+        let wait = (engine.rules.lock_delay / FRAME) as usize - 5;
+        for i in 1..engine.rules.max_lock_resets as usize {
+            for _ in 0..wait {
+                assert!(!locked(&press(&mut engine, InputFrame::default())));
+            }
+            assert!(!locked(&press(&mut engine, moves[i % moves.len()])));
+        }
+        // The last reset allowed leaves the piece nothing to wait for
+        assert!(locked(&press(&mut engine, moves[0])));
+    }
+
+    #[test]
+    fn new_lowest_row_refills_the_resets() {
+        let mut engine = new_engine();
+        fill(&mut engine, &["....######"]);
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::O, 3, floor - 2);
+        for _ in 0..10 {
+            press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+        }
+        assert_eq!(engine.lock_resets, 10);
+
+        // Two steps left and the O hangs over the edge of the ledge
+        press(&mut engine, InputFrame { left: true, ..Default::default() });
+        press(&mut engine, InputFrame { left: true, ..Default::default() });
+        while !engine.is_grounded() {
+            assert!(!locked(&press(&mut engine, InputFrame::default())));
+        }
+        assert_eq!(engine.current_piece.pos.y, floor - 1);
+        assert_eq!(engine.lock_resets, 0);
+    }
+
+    /// Hard drops every piece where it spawns and notes each kind, until the game ends.
+    fn drop_order(seed: u64) -> Vec<BiduleType> {
+        let mut engine = Engine::new(GameSeed::new(seed));
+        let mut kinds = Vec::new();
+        while engine.phase == Phase::Playing && kinds.len() < 30 {
+            kinds.push(engine.current_piece.kind);
+            press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+        }
+        kinds
+    }
+
+    #[test]
+    fn same_seed_same_piece_order() {
+        let order = drop_order(42);
+        assert!(order.len() > 7);
+        assert_eq!(order, drop_order(42));
+    }
+
+    #[test]
+    fn hold_swapping_onto_the_stack_blocks_out() {
+        let mut engine = new_engine();
+        engine.hold_piece = Some(Bidule::new(BiduleType::O, 1, &mut engine.seed.cosmetic));
+        let spawn = engine.grid.spawn_position(BiduleType::O.box_size(), 1);
+        engine.grid.cells[spawn.y as usize + 1][spawn.x as usize + 1] = Some(block());
+        press(&mut engine, InputFrame { hold: true, ..Default::default() });
+
+        assert_eq!(engine.phase, Phase::GameOver);
+        assert_eq!(engine.top_out, Some(TopOut::Block));
+    }
+
+    #[test]
+    fn tspin_double_puzzle_is_solved() {
+        let puzzles = crate::puzzle::parse(crate::puzzle::BUILTIN_PUZZLES).unwrap();
+        let puzzle = puzzles.into_iter().find(|p| p.name == "T-Spin Double").unwrap();
+        let rules = Rules {
+            mode: GameMode::Puzzle,
+            board_width: puzzle.width(),
+            puzzle: Some(puzzle),
+            ..Default::default()
+        };
+        let mut engine = Engine::with_rules(rules, GameSeed::new(1));
+
+        press(&mut engine, InputFrame { left: true, ..Default::default() });
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+        while !engine.is_grounded() {
+            press(&mut engine, InputFrame { soft_drop: true, ..Default::default() });
+        }
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+        press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+
+        assert_eq!(engine.phase, Phase::Finished);
+        let gained: i32 = engine
+            .score_log
+            .iter()
+            .filter(|e| e.source == ScoreSource::LineClear)
+            .map(|e| e.gain)
+            .sum();
+        assert_eq!(gained, 1200);
+    }
+
+    #[test]
+    fn cascade_chains_a_second_clear() {
+        let mut engine = new_engine();
+        engine.rules.cascade = true;
+        fill(&mut engine, &["#.........", "######....", ".#########"]);
+        place(&mut engine, BiduleType::I, 6, 0);
+        // Clearing the middle row leaves the top left block floating over the bottom hole
+        let mut events = press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+        while engine.phase == Phase::Cascading {
+            events.extend(press(&mut engine, InputFrame::default()));
+        }
+
+        let steps: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                GameEvent::LinesCleared { chain, .. } => Some(format!("clear {}", chain)),
+                GameEvent::CascadeFell { .. } => Some("fell".to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(steps, ["clear 1", "fell", "clear 2"]);
+        assert_eq!(blocks(&engine), 0);
+        let chain = engine.score_log.iter().find(|e| e.source == ScoreSource::Chain).unwrap();
+        assert_eq!(chain.base, scoring::chain_points(1, 2));
+    }
+
+    /// The spin reported by the clear a hard drop makes on the T-spin ledge below.
+    fn hard_drop_spin(engine: &mut Engine) -> TSpin {
+        let events = press(engine, InputFrame { hard_drop: true, ..Default::default() });
+        events
+            .iter()
+            .find_map(|e| match e {
+                GameEvent::LinesCleared { tspin, .. } => Some(*tspin),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// An upright T fits under the top left block with its nub on the bottom row.
+    const SPIN_LEDGE: [&str; 3] = ["#.........", "..........", "#.########"];
+
+    #[test]
+    fn rotating_mid_air_then_hard_dropping_is_not_a_spin() {
+        let mut engine = new_engine();
+        fill(&mut engine, &SPIN_LEDGE);
+        place(&mut engine, BiduleType::T, 0, 0);
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+
+        assert_eq!(hard_drop_spin(&mut engine), TSpin::None);
+    }
+
+    #[test]
+    fn rotating_in_the_slot_then_hard_dropping_is_a_spin() {
+        let mut engine = new_engine();
+        fill(&mut engine, &SPIN_LEDGE);
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::T, 0, floor - 2);
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+
+        assert_ne!(hard_drop_spin(&mut engine), TSpin::None);
+    }
+
+    #[test]
+    fn chain_steps_leave_back_to_back_alone() {
+        let mut engine = new_engine();
+        engine.rules.cascade = true;
+        fill(
+            &mut engine,
+            &[
+                "#.........",
+                "#########.",
+                "#########.",
+                "#########.",
+                "#########.",
+                ".#########",
+            ],
+        );
+        place(&mut engine, BiduleType::I, 7, 0);
+        engine.current_piece.rotate(Rotation::Clockwise);
+        // A tetris, then the top left block falls in for a single
+        press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+        while engine.phase == Phase::Cascading {
+            press(&mut engine, InputFrame::default());
+        }
+
+        assert_eq!(engine.lines_cleared_total, 5);
+        assert!(engine.back_to_back);
+    }
+
+    #[test]
+    fn auto_repeat_starts_when_das_runs_out() {
+        // Sixty-fourths add up exactly, so the shifts land on the frames counted here
+        const TICK: f32 = 1.0 / 64.0;
+        let rules = Rules {
+            das: 8.0 * TICK,
+            arr: 2.0 * TICK,
+            ..Default::default()
+        };
+        let mut engine = Engine::with_rules(rules, GameSeed::new(1));
+        place(&mut engine, BiduleType::T, 3, 2);
+        engine.step(TICK, &InputFrame { right: true, right_held: true, ..Default::default() });
+        assert_eq!(engine.current_piece.pos.x, 4);
+
+        let held = InputFrame { right_held: true, ..Default::default() };
+        let mut columns = Vec::new();
+        for _ in 0..12 {
+            engine.step(TICK, &held);
+            columns.push(engine.current_piece.pos.x);
+        }
+        // DAS, DAS + ARR and DAS + 2 ARR after the press
+        assert_eq!(columns, [4, 4, 4, 4, 4, 4, 4, 5, 5, 6, 6, 7]);
+    }
+
+    #[test]
+    fn gold_pickaxe_skips_a_spin_that_clears_nothing() {
+        let mut engine = new_engine();
+        engine.activate_bonus(BonusType::GoldenPickaxe);
+        // The ledge with a gap at the end of the bottom row, so the spin clears nothing
+        fill(&mut engine, &["#.........", "..........", "#.#######."]);
+        let floor = floor(&engine);
+        place(&mut engine, BiduleType::T, 0, floor - 2);
+        press(&mut engine, InputFrame { rotate_cw: true, ..Default::default() });
+        let events = press(&mut engine, InputFrame { hard_drop: true, ..Default::default() });
+
+        assert!(events.iter().any(|e| matches!(e, GameEvent::TSpinNoLines { .. })));
+        let spin = engine.score_log.iter().find(|e| e.source == ScoreSource::LineClear).unwrap();
+        assert!(spin.modifiers.is_empty());
+        assert_eq!(spin.gain, spin.base);
+    }
+}
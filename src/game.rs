@@ -1,9 +1,17 @@
 use crate::background::NatureBackground;
-use crate::bidule::{Bidule, BiduleType};
-use crate::bonuses::{ActiveBonus, Bonus};
+use crate::bidule::Point;
+use crate::bonuses::{Bonus, BonusType};
 use crate::constants::*;
+use crate::draw;
 use crate::effects::{ComicEffect, Particle, ParticleType};
-use crate::grid::Grid;
+use crate::engine::{Engine, GameEvent, InputFrame, Phase, Rules};
+use crate::gravity::GravityCurve;
+use crate::mode::GameMode;
+use crate::puzzle::{self, Puzzle};
+use crate::randomizer::RandomizerKind;
+use crate::records::Records;
+use crate::scoring::TSpin;
+use crate::seed::GameSeed;
 use crate::sound_effects::AudioSystem;
 use macroquad::prelude::*;
 use macroquad::text::Font;
@@ -14,16 +22,42 @@ pub enum GameState {
     Playing,
     ChooseBonus,
     GameOver,
+    /// The mode's goal was reached
+    Results,
+    /// The engine is not stepped, so every clock stops
+    Paused,
+    /// Picking a puzzle before a Puzzle mode game
+    PuzzleSelect,
 }
 
+// Start menu rows
+pub const MENU_START: usize = 0;
+pub const MENU_MODE: usize = 1;
+pub const MENU_SEED: usize = 2;
+pub const MENU_RANDOMIZER: usize = 3;
+pub const MENU_BOARD: usize = 4;
+pub const MENU_GARBAGE: usize = 5;
+pub const MENU_GRAVITY: usize = 6;
+pub const MENU_NEXT: usize = 7;
+pub const MENU_CASCADE: usize = 8;
+pub const MENU_MUSIC: usize = 9;
+pub const MENU_EXIT: usize = 10;
+pub const MENU_ITEMS: usize = 11;
+
+/// Board sizes (width, height) offered on the start menu
+pub const BOARD_PRESETS: [(usize, usize); 4] = [
+    (GRID_WIDTH, GRID_HEIGHT),
+    (4, 20),  // Training well
+    (20, 20), // Co-op width
+    (10, 40), // Tall
+];
+
+/// Rising garbage settings (label, seconds between lines) offered on the start menu
+pub const GARBAGE_PRESETS: [(&str, f32); 3] = [("OFF", 0.0), ("SLOW", 10.0), ("FAST", 4.0)];
+
+/// Macroquad frontend: menus, input, audio and juice around the headless `Engine`.
 pub struct Game {
-    pub grid: Grid,
-    pub current_piece: Bidule,
-    pub next_pieces: Vec<Bidule>,
-    pub hold_piece: Option<Bidule>,
-    pub can_hold: bool,
-    pub score: i32,
-    pub last_fall_time: f64,
+    pub engine: Engine,
     // Visuals
     pub effects: Vec<ComicEffect>,
     pub particles: Vec<Particle>,
@@ -32,78 +66,71 @@ pub struct Game {
     pub audio: AudioSystem,
     pub is_music_playing: bool,
     pub state: GameState,
-    pub level: i32,
-    pub lines_cleared_total: i32,
     pub screen_shake: f32,
     pub ui_pulse: f32, // Timer for UI juice
-    pub menu_selection: usize, // 0: Start, 1: Options, 2: Exit
-    bag: Vec<BiduleType>,
-    
+    pub show_breakdown: bool, // Score breakdown over the game over screen
+    pub menu_selection: usize, // See MENU_* rows
+    pub mode: GameMode,
+    pub seed_input: String,    // Typed on the start menu, empty for a random seed
+    pub randomizer: RandomizerKind,
+    pub board_preset: usize, // Index into BOARD_PRESETS
+    pub garbage_preset: usize, // Index into GARBAGE_PRESETS
+    pub gravity: GravityCurve,
+    pub next_queue: usize, // 0..=MAX_NEXT_QUEUE
+    pub cascade: bool,
+    /// Cells still sliding to where cascade gravity put them: board position, rows left
+    pub falling: Vec<(Point, f32)>,
+
     // Bonus System
-    pub bonus_options: Vec<Bonus>,
     pub bonus_selection_idx: usize,
-    pub active_bonuses: Vec<ActiveBonus>,
+
+    pub records: Records,
+    pub new_best: bool, // The finished game set a personal best
+
+    pub puzzles: Vec<Puzzle>,
+    pub puzzle_selection: usize,
 }
 
 impl Game {
     pub fn new(font: Option<Font>, audio: AudioSystem) -> Self {
-        let mut game = Self {
-            grid: Grid::new(),
-            current_piece: Bidule::new(BiduleType::I), // Placeholder
-            next_pieces: Vec::new(),
-            hold_piece: None,
-            can_hold: true,
-            score: 0,
-            last_fall_time: get_time(),
+        Self {
+            engine: Engine::new(GameSeed::random()),
             effects: Vec::new(),
             particles: Vec::new(),
             background: NatureBackground::new(),
             font,
             audio,
-            bag: Vec::new(),
             is_music_playing: true,
             state: GameState::Start,
-            level: 1,
-            lines_cleared_total: 0,
             screen_shake: 0.0,
             ui_pulse: 0.0,
+            show_breakdown: false,
             menu_selection: 0,
-            
+            mode: GameMode::Marathon,
+            seed_input: String::new(),
+            randomizer: RandomizerKind::SevenBag,
+            board_preset: 0,
+            garbage_preset: 0,
+            gravity: GravityCurve::Guideline,
+            next_queue: 3,
+            cascade: false,
+            falling: Vec::new(),
+
             // Bonus System
-            bonus_options: Vec::new(),
             bonus_selection_idx: 0,
-            active_bonuses: Vec::new(),
-        };
-
-        game.fill_bag();
-        game.current_piece = game.get_next_piece();
-        for _ in 0..3 {
-            let p = game.get_next_piece();
-            game.next_pieces.push(p);
-        }
-
-        game
-    }
 
-    fn fill_bag(&mut self) {
-        let mut types = vec![
-            BiduleType::I,
-            BiduleType::O,
-            BiduleType::T,
-            BiduleType::S,
-            BiduleType::Z,
-            BiduleType::J,
-            BiduleType::L,
-        ];
-        fastrand::shuffle(&mut types);
-        self.bag.extend(types);
-    }
+            records: Records::load(),
+            new_best: false,
 
-    fn get_next_piece(&mut self) -> Bidule {
-        if self.bag.is_empty() {
-            self.fill_bag();
+            puzzles: match puzzle::parse(puzzle::BUILTIN_PUZZLES) {
+                Ok(puzzles) => puzzles,
+                Err(e) => {
+                    println!("Failed to load puzzles: {}", e);
+                    Vec::new()
+                }
+            },
+            puzzle_selection: 0,
         }
-        Bidule::new(self.bag.pop().unwrap())
     }
 
     pub fn update(&mut self) {
@@ -117,6 +144,12 @@ impl Game {
             }
         }
 
+        // Cascade slides
+        for (_, rows) in self.falling.iter_mut() {
+            *rows -= dt * CASCADE_FALL_SPEED;
+        }
+        self.falling.retain(|(_, rows)| *rows > 0.0);
+
         // UI Pulse decay
         if self.ui_pulse > 0.0 {
             self.ui_pulse -= dt;
@@ -128,30 +161,109 @@ impl Game {
         match self.state {
             GameState::Start => {
                 if is_key_pressed(KeyCode::Down) {
-                    self.menu_selection = (self.menu_selection + 1) % 3;
+                    self.menu_selection = (self.menu_selection + 1) % MENU_ITEMS;
                     self.audio.play_hold(); // reusing a bloop sound
                 }
                 if is_key_pressed(KeyCode::Up) {
                     if self.menu_selection == 0 {
-                        self.menu_selection = 2;
+                        self.menu_selection = MENU_ITEMS - 1;
                     } else {
                         self.menu_selection -= 1;
                     }
                     self.audio.play_hold();
                 }
 
+                // Seed entry: digits only, empty means random
+                if self.menu_selection == MENU_SEED {
+                    while let Some(c) = get_char_pressed() {
+                        if c.is_ascii_digit() && self.seed_input.len() < 19 {
+                            self.seed_input.push(c);
+                        }
+                    }
+                    if is_key_pressed(KeyCode::Backspace) {
+                        self.seed_input.pop();
+                    }
+                }
+
+                if self.menu_selection == MENU_MODE {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_mode(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_mode(GameMode::ALL.len() - 1);
+                    }
+                }
+                if self.menu_selection == MENU_RANDOMIZER {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_randomizer(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_randomizer(RandomizerKind::ALL.len() - 1);
+                    }
+                }
+                if self.menu_selection == MENU_BOARD {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_board(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_board(BOARD_PRESETS.len() - 1);
+                    }
+                }
+                if self.menu_selection == MENU_GRAVITY {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_gravity(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_gravity(GravityCurve::ALL.len() - 1);
+                    }
+                }
+                if self.menu_selection == MENU_NEXT {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_next_queue(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_next_queue(MAX_NEXT_QUEUE);
+                    }
+                }
+                if self.menu_selection == MENU_CASCADE
+                    && (is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::Left))
+                {
+                    self.toggle_cascade();
+                }
+                if self.menu_selection == MENU_GARBAGE {
+                    if is_key_pressed(KeyCode::Right) {
+                        self.cycle_garbage(1);
+                    }
+                    if is_key_pressed(KeyCode::Left) {
+                        self.cycle_garbage(GARBAGE_PRESETS.len() - 1);
+                    }
+                }
+
                 if is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Enter) {
                     match self.menu_selection {
-                        0 => {
+                        MENU_START if self.mode == GameMode::Puzzle => self.open_puzzle_select(),
+                        MENU_START => {
+                            self.engine = self.new_engine();
                             self.state = GameState::Playing;
                             self.audio.play_level_up(); // Confirm sound
                         }
-                        1 => {
+                        MENU_SEED => {
+                            // Seed: clear back to random
+                            self.seed_input.clear();
+                        }
+                        MENU_MODE => self.cycle_mode(1),
+                        MENU_RANDOMIZER => self.cycle_randomizer(1),
+                        MENU_BOARD => self.cycle_board(1),
+                        MENU_GARBAGE => self.cycle_garbage(1),
+                        MENU_GRAVITY => self.cycle_gravity(1),
+                        MENU_NEXT => self.cycle_next_queue(1),
+                        MENU_CASCADE => self.toggle_cascade(),
+                        MENU_MUSIC => {
                             // Option: Toggle Music
                             self.is_music_playing = !self.is_music_playing;
                             self.audio.toggle_music(self.is_music_playing);
                         }
-                        2 => {
+                        MENU_EXIT => {
                             // Exit
                             std::process::exit(0);
                         }
@@ -160,13 +272,14 @@ impl Game {
                 }
             }
             GameState::ChooseBonus => {
-                 if is_key_pressed(KeyCode::Right) {
-                    self.bonus_selection_idx = (self.bonus_selection_idx + 1) % self.bonus_options.len();
+                let option_count = self.engine.bonus_options.len().max(1);
+                if is_key_pressed(KeyCode::Right) {
+                    self.bonus_selection_idx = (self.bonus_selection_idx + 1) % option_count;
                     self.audio.play_hold();
                 }
                 if is_key_pressed(KeyCode::Left) {
                     if self.bonus_selection_idx == 0 {
-                        self.bonus_selection_idx = self.bonus_options.len() - 1;
+                        self.bonus_selection_idx = option_count - 1;
                     } else {
                         self.bonus_selection_idx -= 1;
                     }
@@ -174,134 +287,211 @@ impl Game {
                 }
                 if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
                     // Activate Bonus
-                    if let Some(bonus) = self.bonus_options.get(self.bonus_selection_idx) {
-                        self.activate_bonus(bonus.clone());
+                    if let Some(bonus) = self.engine.choose_bonus(self.bonus_selection_idx) {
+                        self.show_bonus_activation(&bonus);
                     }
                     self.state = GameState::Playing;
                     self.audio.play_level_up();
                 }
             }
             GameState::Playing => {
-                let time = get_time();
+                self.handle_music_button();
 
-                self.handle_input();
+                if is_key_pressed(KeyCode::P) || is_key_pressed(KeyCode::Escape) {
+                    self.state = GameState::Paused;
+                    self.audio.play_hold();
+                    return;
+                }
 
-                let is_soft_drop = is_key_down(KeyCode::Down);
-                let speed = if is_soft_drop {
-                    0.05
-                } else {
-                    // Level-based speed
-                    let base_speed = (0.5 * (0.9f64.powi(self.level - 1))).max(0.05);
-                    
-                    // CHILL Bonus: 50% slower
-                    let mut speed_mod = 1.0;
-                    if self.active_bonuses.iter().any(|b| b.kind == crate::bonuses::BonusType::Chill) {
-                        speed_mod *= 1.5;
-                    }
-                    
-                    // TIME ANCHOR: 10% slower per stack
-                    let anchors = self.active_bonuses.iter().filter(|b| b.kind == crate::bonuses::BonusType::TimeAnchor).count();
-                    if anchors > 0 {
-                        speed_mod *= 1.0 + (0.1 * anchors as f64);
-                    }
-                    
-                    base_speed * speed_mod
-                };
+                let input = read_input();
+                let events = self.engine.step(dt, &input);
+                for event in events {
+                    self.handle_event(event);
+                }
 
-                if time - self.last_fall_time > speed {
-                    self.current_piece.pos.y += 1;
-                    if self.grid.is_collision(&self.current_piece) {
-                        self.current_piece.pos.y -= 1;
-                        self.lock_and_spawn();
+                match self.engine.phase {
+                    Phase::ChooseBonus => {
+                        self.state = GameState::ChooseBonus;
+                        self.bonus_selection_idx = 1; // Center default
+                    }
+                    Phase::GameOver => self.state = GameState::GameOver,
+                    Phase::Finished => {
+                        let ranked = self.engine.rules.is_standard();
+                        self.new_best = match self.mode {
+                            GameMode::Sprint => ranked && self.records.submit_sprint(self.engine.elapsed),
+                            GameMode::Ultra => ranked && self.records.submit_ultra(self.engine.score),
+                            GameMode::Dig => ranked && self.records.submit_dig(self.engine.elapsed),
+                            GameMode::Puzzle => false,
+                            GameMode::Marathon
+                            | GameMode::Pentomino
+                            | GameMode::Big
+                            | GameMode::Fading
+                            | GameMode::Invisible => false,
+                        };
+                        self.state = GameState::Results;
                     }
-                    self.last_fall_time = time;
+                    Phase::Playing | Phase::Cascading => {}
                 }
             }
             GameState::GameOver => {
+                if is_key_pressed(KeyCode::B) {
+                    self.show_breakdown = !self.show_breakdown;
+                    self.audio.play_hold();
+                }
+                if is_key_pressed(KeyCode::R) {
+                    self.restart();
+                }
+            }
+            GameState::Results => {
                 if is_key_pressed(KeyCode::R) {
-                    let font = self.font.take();
-                    let audio = self.audio.clone();
-                    *self = Game::new(font, audio);
-                    self.state = GameState::Playing; // Start immediately on reset
+                    self.restart();
+                }
+            }
+            GameState::PuzzleSelect => {
+                let count = self.puzzles.len().max(1);
+                if is_key_pressed(KeyCode::Down) {
+                    self.puzzle_selection = (self.puzzle_selection + 1) % count;
+                    self.audio.play_hold();
+                }
+                if is_key_pressed(KeyCode::Up) {
+                    self.puzzle_selection = (self.puzzle_selection + count - 1) % count;
+                    self.audio.play_hold();
+                }
+                if is_key_pressed(KeyCode::Escape) {
+                    self.state = GameState::Start;
+                }
+                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+                    self.engine = self.new_engine();
+                    self.state = GameState::Playing;
+                    self.audio.play_level_up();
+                }
+            }
+            GameState::Paused => {
+                if is_key_pressed(KeyCode::P) || is_key_pressed(KeyCode::Escape) {
+                    self.state = GameState::Playing;
+                    self.audio.play_hold();
                 }
             }
         }
 
         self.effects.retain_mut(|e| e.update());
         self.particles.retain_mut(|p| p.update());
-        
-        // Update active bonuses
-        let dt = get_frame_time();
-        self.active_bonuses.retain_mut(|b| {
-            b.timer -= dt;
-            b.timer > 0.0
-        });
 
         self.background.update();
     }
 
-    fn handle_input(&mut self) {
-        if is_key_pressed(KeyCode::Left) {
-            self.current_piece.pos.x -= 1;
-            if self.grid.is_collision(&self.current_piece) {
-                self.current_piece.pos.x += 1;
-            }
-        }
-        if is_key_pressed(KeyCode::Right) {
-            self.current_piece.pos.x += 1;
-            if self.grid.is_collision(&self.current_piece) {
-                self.current_piece.pos.x -= 1;
-            }
+    /// On-screen cell size of the current board, for placing effects.
+    fn block_size(&self) -> f32 {
+        draw::board_block_size(self.engine.grid.width, self.engine.grid.height)
+    }
+
+    /// On-screen offset of a board row from the top of the visible board.
+    fn row_y(&self, row: i32) -> f32 {
+        (row - self.engine.grid.buffer as i32) as f32 * self.block_size()
+    }
+
+    /// Starts over right away with the same menu choices.
+    /// A typed seed replays the same game, otherwise a new one is rolled.
+    fn restart(&mut self) {
+        self.engine = self.new_engine();
+        self.effects.clear();
+        self.particles.clear();
+        self.falling.clear();
+        self.screen_shake = 0.0;
+        self.ui_pulse = 0.0;
+        self.show_breakdown = false;
+        self.bonus_selection_idx = 0;
+        self.new_best = false;
+        self.state = GameState::Playing;
+    }
+
+    /// Builds a fresh engine from the start menu choices.
+    fn new_engine(&self) -> Engine {
+        let (mut board_width, mut board_height) = BOARD_PRESETS[self.board_preset];
+        let puzzle = match self.mode {
+            GameMode::Puzzle => self.puzzles.get(self.puzzle_selection).cloned(),
+            _ => None,
+        };
+        if let Some(puzzle) = &puzzle {
+            // Puzzles are drawn for a fixed width
+            board_width = puzzle.width();
+        } else {
+            let scale = self.mode.block_scale() as usize;
+            board_width = (board_width + self.mode.piece_set().extra_columns()) * scale;
+            board_height *= scale;
         }
+        let rules = Rules {
+            puzzle,
+            mode: self.mode,
+            randomizer: self.randomizer,
+            board_width,
+            board_height,
+            rising_garbage: GARBAGE_PRESETS[self.garbage_preset].1,
+            gravity: self.gravity,
+            next_queue: self.next_queue,
+            cascade: self.cascade,
+            ..Default::default()
+        };
+        Engine::with_rules(rules, self.chosen_seed())
+    }
 
-        if is_key_pressed(KeyCode::Up) {
-            let mut rotated = self.current_piece.clone();
-            rotated.rotate();
-            if !self.grid.is_collision(&rotated) {
-                self.current_piece = rotated;
-            } else {
-                // Wall kick (simple)
-                rotated.pos.x += 1;
-                if !self.grid.is_collision(&rotated) {
-                    self.current_piece = rotated;
-                } else {
-                    rotated.pos.x -= 2;
-                    if !self.grid.is_collision(&rotated) {
-                        self.current_piece = rotated;
-                    }
-                }
-            }
+    fn open_puzzle_select(&mut self) {
+        if !self.puzzles.is_empty() {
+            self.state = GameState::PuzzleSelect;
+            self.audio.play_hold();
         }
+    }
 
-        if is_key_pressed(KeyCode::C) {
-            if self.can_hold {
-                self.audio.play_hold();
-                if let Some(mut held) = self.hold_piece.clone() {
-                    let mut current = self.current_piece.clone();
-                    // Reset position
-                    current.pos = crate::bidule::Point { x: 3, y: 0 };
-                    held.pos = crate::bidule::Point { x: 3, y: 0 };
-
-                    self.hold_piece = Some(Bidule::new(current.kind)); // Reset orientation
-                    self.current_piece = Bidule::new(held.kind);
-                } else {
-                    let current = self.current_piece.clone();
-                    self.hold_piece = Some(Bidule::new(current.kind));
+    fn cycle_mode(&mut self, step: usize) {
+        let all = GameMode::ALL;
+        let idx = all.iter().position(|&m| m == self.mode).unwrap_or(0);
+        self.mode = all[(idx + step) % all.len()];
+        self.audio.play_hold();
+    }
 
-                    // Pop next piece
-                    self.current_piece = self.next_pieces.remove(0);
-                    let p = self.get_next_piece();
-                    self.next_pieces.push(p);
-                }
-                self.can_hold = false;
-            }
-        }
+    fn cycle_randomizer(&mut self, step: usize) {
+        let all = RandomizerKind::ALL;
+        let idx = all.iter().position(|&k| k == self.randomizer).unwrap_or(0);
+        self.randomizer = all[(idx + step) % all.len()];
+        self.audio.play_hold();
+    }
+
+    fn cycle_board(&mut self, step: usize) {
+        self.board_preset = (self.board_preset + step) % BOARD_PRESETS.len();
+        self.audio.play_hold();
+    }
 
-        if is_key_pressed(KeyCode::Space) {
-            self.current_piece.pos = self.get_ghost_position();
-            self.lock_and_spawn();
+    fn cycle_garbage(&mut self, step: usize) {
+        self.garbage_preset = (self.garbage_preset + step) % GARBAGE_PRESETS.len();
+        self.audio.play_hold();
+    }
+
+    fn cycle_gravity(&mut self, step: usize) {
+        let all = GravityCurve::ALL;
+        let idx = all.iter().position(|&g| g == self.gravity).unwrap_or(0);
+        self.gravity = all[(idx + step) % all.len()];
+        self.audio.play_hold();
+    }
+
+    fn toggle_cascade(&mut self) {
+        self.cascade = !self.cascade;
+        self.audio.play_hold();
+    }
+
+    fn cycle_next_queue(&mut self, step: usize) {
+        self.next_queue = (self.next_queue + step) % (MAX_NEXT_QUEUE + 1);
+        self.audio.play_hold();
+    }
+
+    /// Seed typed on the start menu, or a fresh random one.
+    fn chosen_seed(&self) -> GameSeed {
+        match self.seed_input.parse::<u64>() {
+            Ok(value) => GameSeed::new(value),
+            Err(_) => GameSeed::random(),
         }
+    }
 
+    fn handle_music_button(&mut self) {
         if is_mouse_button_pressed(MouseButton::Left) {
             let (mx, my) = mouse_position();
 
@@ -317,174 +507,117 @@ impl Game {
         }
     }
 
-    pub fn get_ghost_position(&self) -> crate::bidule::Point {
-        let mut ghost = self.current_piece.clone();
-        while !self.grid.is_collision(&ghost) {
-            ghost.pos.y += 1;
-        }
-        ghost.pos.y -= 1;
-        ghost.pos
-    }
-
-    fn lock_and_spawn(&mut self) {
-        // Play Merge/Lock Sounds based on neighbors
-        let mut same_color = false;
-        let mut diff_color = false;
-        let p_color = self.current_piece.color;
-
-        for p in self.current_piece.positions.iter() {
-            let x = self.current_piece.pos.x + p.x;
-            let y = self.current_piece.pos.y + p.y;
-
-            let neighbors = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-            for (dx, dy) in neighbors {
-                let nx = x + dx;
-                let ny = y + dy;
-
-                if nx >= 0 && nx < GRID_WIDTH as i32 && ny >= 0 && ny < GRID_HEIGHT as i32 {
-                    if let Some(cell) = &self.grid.cells[ny as usize][nx as usize] {
-                        if cell.color == p_color {
-                            same_color = true;
-                        } else {
-                            diff_color = true;
-                        }
-                    }
-                }
+    /// Turns engine events into sounds, particles and callouts.
+    fn handle_event(&mut self, event: GameEvent) {
+        let bs = self.block_size();
+        match event {
+            GameEvent::Held => self.audio.play_hold(),
+            GameEvent::PieceLocked {
+                same_color,
+                diff_color,
+            } => {
+                self.audio.play_land(same_color, diff_color);
             }
-        }
-        self.audio.play_land(same_color, diff_color);
-
-        self.grid.lock_piece(&self.current_piece);
-
-        // --- ONE-TIME BONUSES (Bomb / Laser) ---
-        let mut bonuses_to_remove = Vec::new();
-        for (i, bonus) in self.active_bonuses.iter().enumerate() {
-            match bonus.kind {
-                crate::bonuses::BonusType::Bomb => {
-                    // Explode 3x3 around each block of the locked piece
-                    // Or just around the center? Let's do around each block for massive destruction, or just one big boom?
-                    // Let's do: For each block in the piece, explode radius 1
-                    for p in self.current_piece.positions.iter() {
-                        let cx = self.current_piece.pos.x + p.x;
-                        let cy = self.current_piece.pos.y + p.y;
-                        for dy in -1..=1 {
-                            for dx in -1..=1 {
-                                let nx = cx + dx;
-                                let ny = cy + dy;
-                                if nx >= 0 && nx < GRID_WIDTH as i32 && ny >= 0 && ny < GRID_HEIGHT as i32 {
-                                    self.grid.cells[ny as usize][nx as usize] = None;
-                                    // Visual?
-                                    for _ in 0..5 {
-                                        self.particles.push(Particle::new(
-                                            (nx as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                            (ny as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                            RED, 
-                                            ParticleType::Explosion
-                                        ));
-                                    }
-                                }
-                            }
-                        }
+            GameEvent::Exploded {
+                kind,
+                origin,
+                cells,
+            } => {
+                let (color, count) = if kind == BonusType::Bomb {
+                    (RED, 5)
+                } else {
+                    (ORANGE, 3)
+                };
+                for cell in &cells {
+                    for _ in 0..count {
+                        self.particles.push(Particle::new(
+                            (cell.x as f32 * bs) + bs / 2.0,
+                            self.row_y(cell.y) + bs / 2.0,
+                            color,
+                            ParticleType::Explosion,
+                        ));
                     }
+                }
+                if kind == BonusType::Bomb {
                     self.screen_shake = 30.0;
                     self.audio.play_tetris(); // Boom sound replacement?
-                    bonuses_to_remove.push(i);
+                } else {
+                    self.effects.push(ComicEffect::new(
+                        "BOOM!".to_string(),
+                        origin.x as f32 * bs,
+                        self.row_y(origin.y),
+                        RED,
+                    ));
+                    self.screen_shake = 20.0;
                 }
-                crate::bonuses::BonusType::VerticalLaser => {
-                    // Clear columns occupied by the piece
-                    let mut cols = std::collections::HashSet::new();
-                    for p in self.current_piece.positions.iter() {
-                        cols.insert(self.current_piece.pos.x + p.x);
-                    }
-                    for c in cols {
-                         if c >= 0 && c < GRID_WIDTH as i32 {
-                            for y in 0..GRID_HEIGHT {
-                                self.grid.cells[y][c as usize] = None;
-                                // Sparks along the beam
-                                if fastrand::f32() < 0.3 {
-                                    self.particles.push(Particle::new(
-                                        (c as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                        (y as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                        YELLOW, 
-                                        ParticleType::Spark
-                                    ));
-                                }
-                            }
-                         }
-                    }
-                    self.screen_shake = 10.0;
-                    bonuses_to_remove.push(i);
-                }
-                crate::bonuses::BonusType::Drill => {
-                    // Same as laser basically in this implementation?
-                    // Or maybe it clears *below* the piece?
-                    // Let's implement Drill as clearing the columns BELOW the piece positions
-                    for p in self.current_piece.positions.iter() {
-                        let cx = self.current_piece.pos.x + p.x;
-                        let cy = self.current_piece.pos.y + p.y;
-                        if cx >= 0 && cx < GRID_WIDTH as i32 {
-                             for y in cy..GRID_HEIGHT as i32 {
-                                 if y >= 0 {
-                                     self.grid.cells[y as usize][cx as usize] = None;
-                                 }
-                             }
+            }
+            GameEvent::LaserFired { columns } => {
+                for c in columns {
+                    for y in self.engine.grid.buffer..self.engine.grid.total_height() {
+                        // Sparks along the beam
+                        if fastrand::f32() < 0.3 {
+                            self.particles.push(Particle::new(
+                                (c as f32 * bs) + bs / 2.0,
+                                self.row_y(y as i32) + bs / 2.0,
+                                YELLOW,
+                                ParticleType::Spark,
+                            ));
                         }
                     }
-                    bonuses_to_remove.push(i);
-                }
-                crate::bonuses::BonusType::VolatileGrid => {
-                     // 10% chance to explode 3x3
-                     if fastrand::f32() < 0.10 {
-                        // Explosion logic (copied from Bomb but maybe smaller?)
-                        // Reuse Bomb logic 3x3
-                        for p in self.current_piece.positions.iter() {
-                            let cx = self.current_piece.pos.x + p.x;
-                            let cy = self.current_piece.pos.y + p.y;
-                            for dy in -1..=1 {
-                                for dx in -1..=1 {
-                                    let nx = cx + dx;
-                                    let ny = cy + dy;
-                                    if nx >= 0 && nx < GRID_WIDTH as i32 && ny >= 0 && ny < GRID_HEIGHT as i32 {
-                                        self.grid.cells[ny as usize][nx as usize] = None;
-                                        // Explosion particles
-                                        for _ in 0..3 {
-                                           self.particles.push(Particle::new(
-                                                (nx as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                                (ny as f32 * BLOCK_SIZE) + BLOCK_SIZE/2.0, 
-                                                ORANGE, 
-                                                ParticleType::Explosion
-                                            ));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        self.effects.push(ComicEffect::new("BOOM!".to_string(), 
-                            self.current_piece.pos.x as f32 * BLOCK_SIZE, 
-                            self.current_piece.pos.y as f32 * BLOCK_SIZE, RED));
-                        self.screen_shake = 20.0;
-                     }
                 }
-                _ => {}
+                self.screen_shake = 10.0;
             }
-        }
-        // Remove used bonuses (reverse order to keep indices valid? No, active_bonuses logic needed)
-        // efficient removal:
-        for idx in bonuses_to_remove.iter().rev() {
-             self.active_bonuses.remove(*idx);
-        }
-
-        let cleared_rows = self.grid.clear_lines(); // Now returns Vec<usize>
-        let cleared_count = cleared_rows.len() as i32;
-
-        if cleared_count > 0 {
-            self.lines_cleared_total += cleared_count;
-            self.ui_pulse = 0.5;
-
-            // Level up every 10 lines
-            let new_level = (self.lines_cleared_total / 10) + 1;
-            if new_level > self.level {
-                self.level = new_level;
+            GameEvent::Drilled => {}
+            GameEvent::OutOfPieces => self.screen_shake = 10.0,
+            GameEvent::Finished => {
+                self.audio.play_level_up();
+                self.ui_pulse = 1.0;
+            }
+            GameEvent::GarbageReceived { lines } => {
+                self.screen_shake = (5.0 + lines as f32 * 2.0).min(25.0);
+                self.audio.play_land(false, true);
+            }
+            GameEvent::PerfectClear => self.show_perfect_clear(),
+            GameEvent::CascadeFell { fallen } => {
+                self.falling = fallen.into_iter().map(|(p, rows)| (p, rows as f32)).collect();
+            }
+            GameEvent::LinesCleared {
+                rows,
+                tspin,
+                combo,
+                back_to_back,
+                chain,
+            } => {
+                self.show_line_clear(&rows, tspin);
+
+                let x = (self.engine.grid.width as f32 * bs) / 2.0 + 100.0;
+                if chain > 1 {
+                    let y = self.row_y(rows[0] as i32) + 40.0;
+                    self.effects.push(ComicEffect::new(format!("CHAIN x{}!", chain), x, y, GOLD));
+                }
+                let y = self.row_y(rows[0] as i32) + 160.0;
+                if back_to_back {
+                    self.effects.push(ComicEffect::new("BACK-TO-BACK!".to_string(), x, y, GOLD));
+                }
+                if combo > 0 {
+                    self.effects.push(ComicEffect::new(
+                        format!("COMBO x{}", combo),
+                        x,
+                        y + 50.0,
+                        ORANGE,
+                    ));
+                }
+            }
+            GameEvent::TSpinNoLines { tspin, origin } => {
+                self.effects.push(ComicEffect::new(
+                    tspin_callout(tspin, 0),
+                    origin.x as f32 * bs + 100.0,
+                    self.row_y(origin.y) + 100.0,
+                    COLOR_T,
+                ));
+                self.ui_pulse = 0.3;
+            }
+            GameEvent::LevelUp => {
                 self.audio.play_level_up();
                 self.effects.push(ComicEffect::new(
                     "LEVEL UP!".to_string(),
@@ -492,158 +625,143 @@ impl Game {
                     screen_height() / 2.0,
                     GOLD,
                 ));
-                
-                // TRIGGER BONUS SELECTION
-                self.state = GameState::ChooseBonus;
-                self.bonus_options = crate::bonuses::Bonus::get_random_set(3);
-                self.bonus_selection_idx = 1; // Center default
             }
-
-            if cleared_count == 4 {
-                self.audio.play_tetris();
-                self.screen_shake = 15.0;
-            } else {
-                self.screen_shake = 5.0 * cleared_count as f32;
+            GameEvent::Saved => {
+                self.effects.push(ComicEffect::new(
+                    "SAVED!".to_string(),
+                    screen_width() / 2.0,
+                    screen_height() / 2.0,
+                    PINK,
+                ));
+                self.audio.play_level_up(); // Sound feedback
             }
+            GameEvent::ToppedOut => {}
+        }
+    }
+
+    fn show_line_clear(&mut self, cleared_rows: &[usize], tspin: TSpin) {
+        let bs = self.block_size();
+        // Lines of minos, so Big mode callouts match the score
+        let lines = cleared_rows.len().div_ceil(self.engine.rules.mode.block_scale() as usize);
+        // Particle tier: lines cleared, T-spins count double
+        let cleared_count = match tspin {
+            TSpin::Full => (lines * 2).min(4),
+            _ => lines,
+        };
+        self.ui_pulse = 0.5;
+
+        if cleared_count >= 4 {
+            self.audio.play_tetris();
+            self.screen_shake = 15.0;
+        } else {
+            self.screen_shake = 5.0 * cleared_count as f32;
+        }
 
+        let (text, text_color) = if tspin != TSpin::None {
+            (tspin_callout(tspin, lines), COLOR_T)
+        } else {
             let text = match cleared_count {
                 1 => "ZAP!",
                 2 => "POW!",
                 3 => "BAM!",
                 4 => "KABOOM!",
+                5 => "MEGA KABOOM!",
                 _ => "!",
             };
-            
-            // Effect text center of action
-           let center_y = if !cleared_rows.is_empty() {
-                cleared_rows[0] as f32 * BLOCK_SIZE
-            } else {
-                self.current_piece.pos.y as f32 * BLOCK_SIZE
-            };
+            (text.to_string(), RED)
+        };
 
-            self.effects.push(ComicEffect::new(
-                text.to_string(),
-                (GRID_WIDTH as f32 * BLOCK_SIZE) / 2.0 + 100.0, // Center of grid roughly (offset for UI)
-                 center_y + 100.0,
-                RED,
-            ));
+        // Effect text center of action
+        let center_y = self.row_y(cleared_rows[0] as i32);
 
-            // --- TIERED PARTICLE SPAWNING ---
-            use crate::effects::ParticleType;
-            
-            for &row_y in &cleared_rows {
-                let py = row_y as f32 * BLOCK_SIZE + BLOCK_SIZE / 2.0;
-                
-                // Spawn across the width of the row
-                for x in 0..GRID_WIDTH {
-                    let px = x as f32 * BLOCK_SIZE + BLOCK_SIZE / 2.0;
-                    
-                    // Base Colors
-                    let base_color = match cleared_count {
-                        4 => Color::new(fastrand::f32(), fastrand::f32(), fastrand::f32(), 1.0), // Rainbow
-                        3 => Color::new(1.0, 0.4 + fastrand::f32() * 0.4, 0.0, 1.0), // Orange/Red
-                        2 => Color::new(0.2, 1.0, 0.2, 1.0), // Green/Toxic
-                        _ => Color::new(0.4, 0.6, 1.0, 1.0), // Blue/Water
-                    };
-
-                    match cleared_count {
-                        1 => {
-                            // Tier 1: Small Splash
-                            if fastrand::f32() < 0.3 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
-                            }
-                        },
-                        2 => {
-                            // Tier 2: Splash + Bubbles
-                            if fastrand::f32() < 0.5 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
-                            }
-                            if fastrand::f32() < 0.2 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::Bubble));
-                            }
-                        },
-                        3 => {
-                            // Tier 3: Viscous Goo
-                             if fastrand::f32() < 0.6 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
-                            }
-                            if fastrand::f32() < 0.3 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::GooChunk));
-                            }
-                        },
-                        4 => {
-                             // Tier 4: Total Meltdown
-                            self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
-                            if fastrand::f32() < 0.5 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::Bubble));
-                            }
-                            if fastrand::f32() < 0.5 {
-                                self.particles.push(Particle::new(px, py, base_color, ParticleType::GooChunk));
-                            }
-                        },
-                        _ => {}
+        self.effects.push(ComicEffect::new(
+            text,
+            (self.engine.grid.width as f32 * bs) / 2.0 + 100.0, // Center of grid roughly (offset for UI)
+            center_y + 100.0,
+            text_color,
+        ));
+
+        // --- TIERED PARTICLE SPAWNING ---
+        for &row_y in cleared_rows {
+            self.spawn_row_particles(row_y, cleared_count.min(4));
+        }
+    }
+
+    /// Spawns line-clear particles across one row; `tier` 1-4 escalates from splash to meltdown.
+    fn spawn_row_particles(&mut self, row_y: usize, tier: usize) {
+        let bs = self.block_size();
+        let py = self.row_y(row_y as i32) + bs / 2.0;
+
+        // Spawn across the width of the row
+        for x in 0..self.engine.grid.width {
+            let px = x as f32 * bs + bs / 2.0;
+
+            // Base Colors
+            let base_color = match tier {
+                4 => Color::new(fastrand::f32(), fastrand::f32(), fastrand::f32(), 1.0), // Rainbow
+                3 => Color::new(1.0, 0.4 + fastrand::f32() * 0.4, 0.0, 1.0), // Orange/Red
+                2 => Color::new(0.2, 1.0, 0.2, 1.0), // Green/Toxic
+                _ => Color::new(0.4, 0.6, 1.0, 1.0), // Blue/Water
+            };
+
+            match tier {
+                // Tier 1: Small Splash
+                1 if fastrand::f32() < 0.3 => {
+                    self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
+                }
+                2 => {
+                    // Tier 2: Splash + Bubbles
+                    if fastrand::f32() < 0.5 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
+                    }
+                    if fastrand::f32() < 0.2 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::Bubble));
+                    }
+                }
+                3 => {
+                    // Tier 3: Viscous Goo
+                    if fastrand::f32() < 0.6 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
+                    }
+                    if fastrand::f32() < 0.3 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::GooChunk));
                     }
                 }
+                4 => {
+                    // Tier 4: Total Meltdown
+                    self.particles.push(Particle::new(px, py, base_color, ParticleType::Droplet));
+                    if fastrand::f32() < 0.5 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::Bubble));
+                    }
+                    if fastrand::f32() < 0.5 {
+                        self.particles.push(Particle::new(px, py, base_color, ParticleType::GooChunk));
+                    }
+                }
+                _ => {}
             }
         }
+    }
 
-        self.score += match cleared_count {
-            1 => 100 * self.level,
-            2 => 300 * self.level,
-            3 => 500 * self.level,
-            4 => 800 * self.level,
-            _ => 0,
-        };
-        
-        // Apply Score Multiplier
-        if self.active_bonuses.iter().any(|b| b.kind == crate::bonuses::BonusType::ScoreMultiplier) {
-             self.score *= 2; // Simple double
-        }
-        
-        // Apply Golden Pickaxe (+20% per stack)
-        let pickaxes = self.active_bonuses.iter().filter(|b| b.kind == crate::bonuses::BonusType::GoldenPickaxe).count();
-        if pickaxes > 0 {
-            let mult = 1.0 + 0.2 * pickaxes as f32;
-            self.score = (self.score as f32 * mult) as i32;
-        }
+    fn show_perfect_clear(&mut self) {
+        let bs = self.block_size();
+        self.audio.play_tetris();
+        self.screen_shake = 25.0;
+        self.ui_pulse = 1.0;
 
-        self.current_piece = self.next_pieces.remove(0);
-        let p = self.get_next_piece();
-        self.next_pieces.push(p);
-        self.can_hold = true;
-
-        if self.grid.is_collision(&self.current_piece) {
-             // Life Insurance Check
-             if let Some(pos) = self.active_bonuses.iter().position(|b| b.kind == crate::bonuses::BonusType::LifeInsurance) {
-                 // Consume Life Insurance
-                 self.active_bonuses.remove(pos);
-                 self.grid.cells = [[None; GRID_WIDTH]; GRID_HEIGHT]; // Clear board!
-                 self.effects.push(ComicEffect::new("SAVED!".to_string(), screen_width()/2.0, screen_height()/2.0, PINK));
-                 self.audio.play_level_up(); // Sound feedback
-             } else {
-                 self.state = GameState::GameOver;
-             }
+        // The whole (now empty) board melts down
+        for row_y in self.engine.grid.buffer..self.engine.grid.total_height() {
+            self.spawn_row_particles(row_y, 4);
         }
-    }
 
-    pub fn activate_bonus(&mut self, bonus: Bonus) {
-        use crate::bonuses::BonusType;
-        
-        // Some bonuses might have immediate effects, others are stored
-        let duration = match bonus.kind {
-            BonusType::Chill => 60.0, // 60 seconds
-            BonusType::ScoreMultiplier => 60.0, // Lasts for 60s
-            // Relics are Infinite
-            BonusType::TimeAnchor | BonusType::GoldenPickaxe | BonusType::VolatileGrid | BonusType::LifeInsurance => 999999.0,
-            // Others are "One Time Use" on next lock
-            _ => 9999.0, // Until used
-        };
-
-        self.active_bonuses.push(ActiveBonus {
-            kind: bonus.kind,
-            timer: duration,
-        });
+        self.effects.push(ComicEffect::new(
+            "PERFECT CLEAR!".to_string(),
+            (self.engine.grid.width as f32 * bs) / 2.0 + 60.0,
+            (self.engine.grid.height as f32 * bs) / 2.0,
+            GOLD,
+        ));
+    }
 
+    fn show_bonus_activation(&mut self, bonus: &Bonus) {
         // Visual Feedback
         self.effects.push(ComicEffect::new(
             format!("BONUS: {}", bonus.name),
@@ -651,24 +769,51 @@ impl Game {
             screen_height() / 2.0 + 50.0,
             bonus.color,
         ));
-        
+
         // Immediate Particle Burst for activation
         let cx = screen_width() / 2.0;
         let cy = screen_height() / 2.0;
         for _ in 0..20 {
-             let ptype = match bonus.kind {
-                 BonusType::Chill => ParticleType::Snowflake,
-                 BonusType::LifeInsurance => ParticleType::Heart,
-                 BonusType::Bomb | BonusType::VolatileGrid => ParticleType::Explosion,
-                 BonusType::VerticalLaser => ParticleType::Spark,
-                 _ => ParticleType::Bubble,
-             };
-             self.particles.push(Particle::new(
-                 (cx - 100.0) + fastrand::f32() * 200.0,
-                 (cy - 100.0) + fastrand::f32() * 200.0,
-                 bonus.color,
-                 ptype
-             ));
+            let ptype = match bonus.kind {
+                BonusType::Chill => ParticleType::Snowflake,
+                BonusType::LifeInsurance => ParticleType::Heart,
+                BonusType::Bomb | BonusType::VolatileGrid => ParticleType::Explosion,
+                BonusType::VerticalLaser => ParticleType::Spark,
+                _ => ParticleType::Bubble,
+            };
+            self.particles.push(Particle::new(
+                (cx - 100.0) + fastrand::f32() * 200.0,
+                (cy - 100.0) + fastrand::f32() * 200.0,
+                bonus.color,
+                ptype,
+            ));
         }
     }
 }
+
+/// Callout for a T-spin, e.g. "T-SPIN DOUBLE!" or "MINI T-SPIN!".
+fn tspin_callout(tspin: TSpin, lines: usize) -> String {
+    let prefix = if tspin == TSpin::Mini { "MINI T-SPIN" } else { "T-SPIN" };
+    match lines {
+        1 => format!("{} SINGLE!", prefix),
+        2 => format!("{} DOUBLE!", prefix),
+        3 => format!("{} TRIPLE!", prefix),
+        _ => format!("{}!", prefix),
+    }
+}
+
+/// Samples the keyboard into an engine input frame.
+fn read_input() -> InputFrame {
+    InputFrame {
+        left: is_key_pressed(KeyCode::Left),
+        right: is_key_pressed(KeyCode::Right),
+        left_held: is_key_down(KeyCode::Left),
+        right_held: is_key_down(KeyCode::Right),
+        rotate_cw: is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::X),
+        rotate_ccw: is_key_pressed(KeyCode::Z),
+        rotate_180: is_key_pressed(KeyCode::A),
+        soft_drop: is_key_down(KeyCode::Down),
+        hard_drop: is_key_pressed(KeyCode::Space),
+        hold: is_key_pressed(KeyCode::C),
+    }
+}
@@ -0,0 +1,101 @@
+// Gravity curves, as data. Speeds are in rows per second so a fast level can
+// move a piece several rows in one frame; `f64::INFINITY` is 20G (straight to the floor).
+
+const FPS: f64 = 60.0;
+const NES_FPS: f64 = 60.0988;
+
+/// NES frames per row for levels 0..=29; 29 and beyond keep the last entry.
+const NES_FRAMES_PER_ROW: [u32; 30] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6, // 0-9
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2, // 10-19
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 1, // 20-29
+];
+
+/// TGM internal gravity: (from TGM level, 1/256ths of a row per frame). 5120 is 20G.
+const TGM_INTERNAL_GRAVITY: [(i32, u32); 30] = [
+    (0, 4),
+    (30, 6),
+    (35, 8),
+    (40, 10),
+    (50, 12),
+    (60, 16),
+    (70, 32),
+    (80, 48),
+    (90, 64),
+    (100, 80),
+    (120, 96),
+    (140, 112),
+    (160, 128),
+    (170, 144),
+    (200, 4),
+    (220, 32),
+    (230, 64),
+    (233, 96),
+    (236, 128),
+    (239, 160),
+    (243, 192),
+    (247, 224),
+    (251, 256),
+    (300, 512),
+    (330, 768),
+    (360, 1024),
+    (400, 1280),
+    (420, 1024),
+    (450, 768),
+    (500, 5120),
+];
+
+/// TGM levels covered by one of our levels (10 lines), so 20G arrives at level 21.
+const TGM_LEVELS_PER_LEVEL: i32 = 25;
+
+/// Selectable gravity curve, stored in `Rules`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GravityCurve {
+    Guideline,
+    Nes,
+    Tgm,
+}
+
+impl GravityCurve {
+    pub const ALL: [GravityCurve; 3] = [GravityCurve::Guideline, GravityCurve::Nes, GravityCurve::Tgm];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GravityCurve::Guideline => "GUIDELINE",
+            GravityCurve::Nes => "NES",
+            GravityCurve::Tgm => "TGM",
+        }
+    }
+
+    /// Fall speed at `level` (starting at 1).
+    pub fn rows_per_second(self, level: i32) -> f64 {
+        let level = level.max(1);
+        match self {
+            GravityCurve::Guideline => {
+                if level >= 20 {
+                    return f64::INFINITY;
+                }
+                // Seconds per row = (0.8 - (level - 1) * 0.007) ^ (level - 1)
+                let n = (level - 1) as f64;
+                1.0 / (0.8 - n * 0.007).powf(n)
+            }
+            GravityCurve::Nes => {
+                let idx = ((level - 1) as usize).min(NES_FRAMES_PER_ROW.len() - 1);
+                NES_FPS / NES_FRAMES_PER_ROW[idx] as f64
+            }
+            GravityCurve::Tgm => {
+                let tgm_level = (level - 1) * TGM_LEVELS_PER_LEVEL;
+                let internal = TGM_INTERNAL_GRAVITY
+                    .iter()
+                    .rev()
+                    .find(|(from, _)| tgm_level >= *from)
+                    .map_or(4, |&(_, g)| g);
+                if internal >= 5120 {
+                    f64::INFINITY
+                } else {
+                    internal as f64 / 256.0 * FPS
+                }
+            }
+        }
+    }
+}
@@ -1,85 +1,341 @@
-use crate::bidule::Bidule;
-use crate::constants::*;
+use crate::bidule::{Bidule, BiduleType, Point};
+use crate::constants::COLOR_GARBAGE;
 use macroquad::prelude::*;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Cell {
     pub color: Color,
     pub bubble_seed: usize,
+    /// Rose from below as garbage rather than placed by the player
+    pub garbage: bool,
+    /// Seconds of play after which the cell fades out (fading and invisible modes)
+    pub fade_at: Option<f64>,
 }
 
+/// Seconds a fading cell takes to go from fully shown to invisible.
+pub const FADE_SECONDS: f64 = 0.4;
+/// Seconds a faded stack shows again after a line clear.
+pub const REVEAL_SECONDS: f64 = 1.0;
+
+impl Cell {
+    /// Opacity at `now` (seconds of play): fully shown until `fade_at`, then fading out.
+    pub fn alpha(&self, now: f64) -> f32 {
+        match self.fade_at {
+            Some(fade_at) => (1.0 - (now - fade_at) / FADE_SECONDS).clamp(0.0, 1.0) as f32,
+            None => 1.0,
+        }
+    }
+}
+
+/// The playfield, indexed `cells[y][x]` with y pointing down.
+/// The first `buffer` rows are the hidden vanish zone above the visible
+/// `height` rows; pieces spawn there and may lock there.
 pub struct Grid {
-    pub cells: [[Option<Cell>; GRID_WIDTH]; GRID_HEIGHT],
+    pub width: usize,
+    pub height: usize,
+    pub buffer: usize,
+    pub cells: Vec<Vec<Option<Cell>>>,
 }
 
 impl Grid {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize, buffer: usize) -> Self {
         Self {
-            cells: [[None; GRID_WIDTH]; GRID_HEIGHT],
+            width,
+            height,
+            buffer,
+            cells: vec![vec![None; width]; buffer + height],
         }
     }
 
-    pub fn is_collision(&self, piece: &Bidule) -> bool {
-        for p in piece.positions.iter() {
-            let x = piece.pos.x + p.x;
-            let y = piece.pos.y + p.y;
+    /// Visible rows plus the vanish zone.
+    pub fn total_height(&self) -> usize {
+        self.buffer + self.height
+    }
 
-            if x < 0 || x >= GRID_WIDTH as i32 || y >= GRID_HEIGHT as i32 {
-                return true;
-            }
+    /// Top-left of a piece's box when it enters the board: centered, rounding left,
+    /// in the two rows just above the visible area. With a `scale` above 1 the box is
+    /// measured in minos and lands on a multiple of `scale`.
+    pub fn spawn_position(&self, box_size: i32, scale: i32) -> Point {
+        Point {
+            x: (self.width as i32 / scale - box_size) / 2 * scale,
+            y: self.buffer as i32 - 2 * scale,
+        }
+    }
 
-            if y < 0 {
-                continue;
-            }
-            if self.cells[y as usize][x as usize].is_some() {
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width as i32 && y >= 0 && y < self.total_height() as i32
+    }
+
+    /// True if the row is part of the hidden vanish zone (or above it).
+    pub fn is_hidden(&self, y: i32) -> bool {
+        y < self.buffer as i32
+    }
+
+    /// Empties the whole board.
+    pub fn clear(&mut self) {
+        for row in self.cells.iter_mut() {
+            row.fill(None);
+        }
+    }
+
+    pub fn is_collision(&self, piece: &Bidule) -> bool {
+        for p in piece.positions.iter() {
+            if self.is_blocked(piece.pos.x + p.x, piece.pos.y + p.y) {
                 return true;
             }
         }
         false
     }
 
-    pub fn lock_piece(&mut self, piece: &Bidule) {
+    /// True for walls, the floor and filled cells. Space above the vanish zone is open.
+    pub fn is_blocked(&self, x: i32, y: i32) -> bool {
+        if x < 0 || x >= self.width as i32 || y >= self.total_height() as i32 {
+            return true;
+        }
+        y >= 0 && self.cells[y as usize][x as usize].is_some()
+    }
+
+    pub fn lock_piece(&mut self, piece: &Bidule, fade_at: Option<f64>) {
         for (i, p) in piece.positions.iter().enumerate() {
-            let x = (piece.pos.x + p.x) as usize;
-            let y = (piece.pos.y + p.y) as usize;
+            let x = piece.pos.x + p.x;
+            let y = piece.pos.y + p.y;
 
-            if x < GRID_WIDTH && y < GRID_HEIGHT {
-                self.cells[y][x] = Some(Cell {
+            if self.in_bounds(x, y) {
+                self.cells[y as usize][x as usize] = Some(Cell {
                     color: piece.color,
                     bubble_seed: piece.seeds[i],
+                    garbage: false,
+                    fade_at,
+                });
+            }
+        }
+    }
+
+    /// Pushes the stack up one row and fills the bottom with garbage, open at the `hole` columns.
+    /// Returns true if blocks were pushed off the top of the vanish zone.
+    pub fn add_garbage_row(&mut self, hole: std::ops::Range<usize>, rng: &mut fastrand::Rng) -> bool {
+        let overflow = self.cells[0].iter().any(|c| c.is_some());
+        self.cells.remove(0);
+        let row = (0..self.width)
+            .map(|x| {
+                (!hole.contains(&x)).then(|| Cell {
+                    color: COLOR_GARBAGE,
+                    bubble_seed: rng.usize(..),
+                    garbage: true,
+                    fade_at: None,
+                })
+            })
+            .collect();
+        self.cells.push(row);
+        overflow
+    }
+
+    /// Fills the bottom of the board from puzzle rows (top to bottom): `Some(None)` is a
+    /// gray block, `Some(Some(kind))` a block colored like `kind`.
+    pub fn load_rows(&mut self, rows: &[Vec<Option<Option<BiduleType>>>], rng: &mut fastrand::Rng) {
+        let top = self.total_height().saturating_sub(rows.len());
+        for (row, cells) in self.cells[top..].iter_mut().zip(rows) {
+            for (cell, block) in row.iter_mut().zip(cells) {
+                *cell = block.map(|kind| Cell {
+                    color: kind.map_or(COLOR_GARBAGE, |k| k.color()),
+                    bubble_seed: rng.usize(..),
+                    garbage: false,
+                    fade_at: None,
+                });
+            }
+        }
+    }
+
+    /// Rows still holding at least one garbage cell.
+    pub fn garbage_rows(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|row| row.iter().flatten().any(|c| c.garbage))
+            .count()
+    }
+
+    /// Shows every fading cell again, at least until `fade_at`.
+    pub fn reveal(&mut self, fade_at: f64) {
+        for cell in self.cells.iter_mut().flatten().flatten() {
+            cell.fade_at = cell.fade_at.map(|t| t.max(fade_at));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().flatten().all(|c| c.is_none())
+    }
+
+    /// Cascade gravity: every group of touching cells that rests on neither the floor nor
+    /// another group falls as one piece, a row at a time, until nothing moves.
+    /// Returns where each moved cell ended up and how many rows it fell.
+    pub fn settle(&mut self) -> Vec<(Point, i32)> {
+        let height = self.total_height();
+        let mut fallen = vec![vec![0; self.width]; height];
+        loop {
+            let groups = self.groups();
+            let mut labels = vec![vec![None; self.width]; height];
+            for (id, group) in groups.iter().enumerate() {
+                for &(x, y) in group {
+                    labels[y][x] = Some(id);
+                }
+            }
+            let mut moved = false;
+            for (id, mut group) in groups.into_iter().enumerate() {
+                let can_fall = group.iter().all(|&(x, y)| {
+                    y + 1 < height && (self.cells[y + 1][x].is_none() || labels[y + 1][x] == Some(id))
                 });
+                if !can_fall {
+                    continue;
+                }
+                // Lowest cells first, so none is overwritten
+                group.sort_by_key(|&(_, y)| std::cmp::Reverse(y));
+                for (x, y) in group {
+                    self.cells[y + 1][x] = self.cells[y][x].take();
+                    fallen[y + 1][x] = fallen[y][x] + 1;
+                    fallen[y][x] = 0;
+                }
+                moved = true;
+            }
+            if !moved {
+                break;
+            }
+        }
+
+        let mut cells = Vec::new();
+        for (y, row) in fallen.iter().enumerate() {
+            for (x, &rows) in row.iter().enumerate() {
+                if rows > 0 {
+                    cells.push((Point { x: x as i32, y: y as i32 }, rows));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Groups of 4-way connected cells, as (x, y) lists.
+    fn groups(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; self.width]; self.total_height()];
+        let mut groups = Vec::new();
+        for y in 0..self.total_height() {
+            for x in 0..self.width {
+                if self.cells[y][x].is_none() || seen[y][x] {
+                    continue;
+                }
+                let mut group = vec![(x, y)];
+                seen[y][x] = true;
+                let mut i = 0;
+                while i < group.len() {
+                    let (cx, cy) = group[i];
+                    let neighbors = [
+                        (cx.wrapping_sub(1), cy),
+                        (cx + 1, cy),
+                        (cx, cy.wrapping_sub(1)),
+                        (cx, cy + 1),
+                    ];
+                    for (nx, ny) in neighbors {
+                        if nx < self.width
+                            && ny < self.total_height()
+                            && self.cells[ny][nx].is_some()
+                            && !seen[ny][nx]
+                        {
+                            seen[ny][nx] = true;
+                            group.push((nx, ny));
+                        }
+                    }
+                    i += 1;
+                }
+                groups.push(group);
             }
         }
+        groups
     }
 
     pub fn clear_lines(&mut self) -> Vec<usize> {
         let mut cleared_rows = Vec::new();
 
         // 1. Identify full lines
-        for y in 0..GRID_HEIGHT {
-            let full_line = (0..GRID_WIDTH).all(|x| self.cells[y][x].is_some());
-            if full_line {
+        for (y, row) in self.cells.iter().enumerate() {
+            if row.iter().all(|c| c.is_some()) {
                 cleared_rows.push(y);
             }
         }
 
-        // 2. Compact grid (if needed)
+        // 2. Compact grid: drop full rows, refill from the top with empty ones
         if !cleared_rows.is_empty() {
-            let mut new_cells = [[None; GRID_WIDTH]; GRID_HEIGHT];
-            let mut target_y = GRID_HEIGHT - 1;
-
-            for src_y in (0..GRID_HEIGHT).rev() {
-                if !cleared_rows.contains(&src_y) {
-                    new_cells[target_y] = self.cells[src_y];
-                    if target_y > 0 {
-                        target_y -= 1;
-                    }
-                }
+            let mut y = 0;
+            self.cells.retain(|_| {
+                let keep = !cleared_rows.contains(&y);
+                y += 1;
+                keep
+            });
+            for _ in 0..cleared_rows.len() {
+                self.cells.insert(0, vec![None; self.width]);
             }
-            // Fill remaining top rows with None (already done by init, but implicit here because target_y stops)
-            self.cells = new_cells;
         }
 
         cleared_rows
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A grid with no vanish zone holding `rows`, top to bottom; `#` is a block.
+    fn grid(rows: &[&str]) -> Grid {
+        let mut grid = Grid::new(rows[0].len(), rows.len(), 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    grid.cells[y][x] = Some(Cell {
+                        color: GRAY,
+                        bubble_seed: 0,
+                        garbage: false,
+                        fade_at: None,
+                    });
+                }
+            }
+        }
+        grid
+    }
+
+    fn rows(grid: &Grid) -> Vec<String> {
+        grid.cells
+            .iter()
+            .map(|row| row.iter().map(|c| if c.is_some() { '#' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn floating_group_falls_to_the_floor() {
+        let mut grid = grid(&["....", ".##.", "....", "...."]);
+        let fallen = grid.settle();
+
+        assert_eq!(rows(&grid), ["....", "....", "....", ".##."]);
+        assert_eq!(fallen.len(), 2);
+        assert!(fallen.iter().all(|&(_, rows)| rows == 2));
+    }
+
+    #[test]
+    fn supported_group_stays_put() {
+        // The overhang is held up by the column it is joined to
+        let board = ["###.", "#...", "#...", "#..."];
+        let mut grid = grid(&board);
+
+        assert!(grid.settle().is_empty());
+        assert_eq!(rows(&grid), board);
+    }
+
+    #[test]
+    fn groups_that_land_together_merge() {
+        let mut grid = grid(&["#...", "#...", ".#..", "....", "...."]);
+        let fallen = grid.settle();
+
+        assert_eq!(rows(&grid), ["....", "....", "....", "#...", "##.."]);
+        let rows_fallen = |x, y| fallen.iter().find(|(p, _)| (p.x, p.y) == (x, y)).map(|&(_, rows)| rows);
+        assert_eq!(rows_fallen(0, 4), Some(3));
+        assert_eq!(rows_fallen(1, 4), Some(2));
+        // Side by side on the floor, the two are now one group
+        assert_eq!(grid.groups().len(), 1);
+    }
+}
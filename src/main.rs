@@ -4,10 +4,19 @@ mod bonuses;    // New module
 mod constants;
 mod draw;
 mod effects;
+mod engine;
 mod game;
+mod gravity;
 mod grid;
+mod mode;
+mod puzzle;
+mod randomizer;
+mod records;
 mod sound_effects;
+mod srs;
 mod rect_utils; // New module
+mod scoring;
+mod seed;
 mod ui;         // New module
 
 use crate::sound_effects::AudioSystem;
@@ -0,0 +1,148 @@
+use crate::bidule::PieceSet;
+
+/// What ends a game and how it is ranked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameMode {
+    /// Endless, with a bonus draft on every level up.
+    Marathon,
+    /// Clear 40 lines as fast as possible.
+    Sprint,
+    /// Score as much as possible in two minutes.
+    Ultra,
+    /// Dig through a messy garbage stack as fast as possible.
+    Dig,
+    /// Reach a puzzle's goal with its fixed pieces.
+    Puzzle,
+    /// Endless, with the 18 pentominoes on a wider board.
+    Pentomino,
+    /// Endless, with every mino drawn as a 2x2 block on a board twice as fine.
+    Big,
+    /// Endless, with locked cells fading out a few seconds after they land.
+    Fading,
+    /// Endless, with locked cells vanishing as soon as they land.
+    Invisible,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 9] = [
+        GameMode::Marathon,
+        GameMode::Sprint,
+        GameMode::Ultra,
+        GameMode::Dig,
+        GameMode::Puzzle,
+        GameMode::Pentomino,
+        GameMode::Big,
+        GameMode::Fading,
+        GameMode::Invisible,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "MARATHON",
+            GameMode::Sprint => "SPRINT 40L",
+            GameMode::Ultra => "ULTRA 2MIN",
+            GameMode::Dig => "DIG 10L",
+            GameMode::Puzzle => "PUZZLE",
+            GameMode::Pentomino => "PENTOMINO",
+            GameMode::Big => "BIG",
+            GameMode::Fading => "FADING",
+            GameMode::Invisible => "INVISIBLE",
+        }
+    }
+
+    /// Lines that finish the game, if any.
+    pub fn line_goal(self) -> Option<i32> {
+        match self {
+            GameMode::Sprint => Some(40),
+            GameMode::Marathon
+            | GameMode::Ultra
+            | GameMode::Dig
+            | GameMode::Puzzle
+            | GameMode::Pentomino
+            | GameMode::Big
+            | GameMode::Fading
+            | GameMode::Invisible => None,
+        }
+    }
+
+    /// Seconds of play that finish the game, if any.
+    pub fn time_limit(self) -> Option<f64> {
+        match self {
+            GameMode::Ultra => Some(120.0),
+            GameMode::Marathon
+            | GameMode::Sprint
+            | GameMode::Dig
+            | GameMode::Puzzle
+            | GameMode::Pentomino
+            | GameMode::Big
+            | GameMode::Fading
+            | GameMode::Invisible => None,
+        }
+    }
+
+    /// Garbage rows the board starts with. Clearing them all finishes the game.
+    pub fn dig_rows(self) -> Option<usize> {
+        match self {
+            GameMode::Dig => Some(10),
+            GameMode::Marathon
+            | GameMode::Sprint
+            | GameMode::Ultra
+            | GameMode::Puzzle
+            | GameMode::Pentomino
+            | GameMode::Big
+            | GameMode::Fading
+            | GameMode::Invisible => None,
+        }
+    }
+
+    /// Modes that race the clock show it in place of the level.
+    pub fn is_timed(self) -> bool {
+        self.line_goal().is_some() || self.time_limit().is_some() || self.dig_rows().is_some()
+    }
+
+    pub fn piece_set(self) -> PieceSet {
+        match self {
+            GameMode::Pentomino => PieceSet::Pentomino,
+            _ => PieceSet::Tetromino,
+        }
+    }
+
+    /// Cells per mino side. Big mode doubles the board in both directions, so pieces
+    /// move sideways and kick in steps of this many cells.
+    pub fn block_scale(self) -> i32 {
+        match self {
+            GameMode::Big => 2,
+            _ => 1,
+        }
+    }
+
+    /// Seconds a locked cell stays visible before fading out, if the stack fades.
+    /// Line clears show it again for a moment, and it all comes back at game over.
+    pub fn stack_fade(self) -> Option<f64> {
+        match self {
+            GameMode::Fading => Some(5.0),
+            GameMode::Invisible => Some(0.0),
+            _ => None,
+        }
+    }
+
+    /// Only Marathon drafts bonuses, so other results stay comparable.
+    pub fn has_bonus_draft(self) -> bool {
+        self == GameMode::Marathon
+    }
+}
+
+/// Letter grade for an Ultra score.
+pub fn ultra_rank(score: i32) -> &'static str {
+    const RANKS: [(i32, &str); 5] = [
+        (120_000, "S"),
+        (80_000, "A"),
+        (50_000, "B"),
+        (25_000, "C"),
+        (10_000, "D"),
+    ];
+    RANKS
+        .iter()
+        .find(|(min, _)| score >= *min)
+        .map_or("E", |&(_, rank)| rank)
+}
@@ -0,0 +1,178 @@
+use crate::bidule::BiduleType;
+use crate::scoring::TSpin;
+
+/// Built-in drills, in the text format read by `parse`.
+pub const BUILTIN_PUZZLES: &str = include_str!("assets/puzzles.txt");
+
+/// What a puzzle asks for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PuzzleGoal {
+    /// Clear at least this many lines in total
+    ClearLines(i32),
+    PerfectClear,
+    TSpinDouble,
+    /// Lock this many pieces without topping out
+    Survive(u32),
+}
+
+impl PuzzleGoal {
+    pub fn describe(self) -> String {
+        match self {
+            PuzzleGoal::ClearLines(n) => format!("CLEAR {} LINES", n),
+            PuzzleGoal::PerfectClear => "PERFECT CLEAR".to_string(),
+            PuzzleGoal::TSpinDouble => "T-SPIN DOUBLE".to_string(),
+            PuzzleGoal::Survive(n) => format!("SURVIVE {} PIECES", n),
+        }
+    }
+
+    /// Checked after every lock, with that lock's clear.
+    pub fn is_met(
+        self,
+        lines_total: i32,
+        pieces_locked: u32,
+        lines: usize,
+        tspin: TSpin,
+        perfect_clear: bool,
+    ) -> bool {
+        match self {
+            PuzzleGoal::ClearLines(n) => lines_total >= n,
+            PuzzleGoal::PerfectClear => perfect_clear,
+            PuzzleGoal::TSpinDouble => tspin == TSpin::Full && lines == 2,
+            PuzzleGoal::Survive(n) => pieces_locked >= n,
+        }
+    }
+}
+
+/// A starting board, a fixed piece sequence, the hold slot and a goal.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub name: String,
+    pub goal: PuzzleGoal,
+    pub pieces: Vec<BiduleType>,
+    pub hold: Option<BiduleType>,
+    /// Bottom rows of the board, top to bottom. `None` is empty, `Some(None)` a gray block,
+    /// `Some(Some(kind))` a block colored like `kind`.
+    pub rows: Vec<Vec<Option<Option<BiduleType>>>>,
+}
+
+impl Puzzle {
+    /// Board width, taken from the rows.
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+}
+
+/// Reads puzzles separated by `---` lines:
+///
+/// ```text
+/// name: TSD Basics
+/// goal: tspin-double        (or: lines N, perfect-clear, survive N)
+/// pieces: T
+/// hold: none                (or a piece letter)
+/// board:
+/// ..........                (`.` empty, `#` gray, a piece letter for a colored block)
+/// ##..######
+/// ```
+///
+/// Blank lines and lines starting with `;` are ignored.
+pub fn parse(text: &str) -> Result<Vec<Puzzle>, String> {
+    let mut puzzles = Vec::new();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with(';'))
+        .peekable();
+
+    while lines.peek().is_some() {
+        let mut name = None;
+        let mut goal = None;
+        let mut pieces = Vec::new();
+        let mut hold = None;
+        let mut rows: Vec<Vec<Option<Option<BiduleType>>>> = Vec::new();
+        let mut in_board = false;
+
+        for (n, line) in lines.by_ref() {
+            if line == "---" {
+                break;
+            }
+            if in_board {
+                let row = line
+                    .chars()
+                    .map(|c| match c {
+                        '.' => Ok(None),
+                        '#' => Ok(Some(None)),
+                        _ => BiduleType::from_letter(c)
+                            .map(|k| Some(Some(k)))
+                            .ok_or(format!("line {}: unknown cell '{}'", n, c)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if rows.first().is_some_and(|r| r.len() != row.len()) {
+                    return Err(format!("line {}: board rows differ in width", n));
+                }
+                rows.push(row);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(format!("line {}: expected `key: value`", n))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "goal" => goal = Some(parse_goal(value).ok_or(format!("line {}: unknown goal", n))?),
+                "pieces" => {
+                    pieces = value
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .map(|c| BiduleType::from_letter(c).ok_or(format!("line {}: unknown piece '{}'", n, c)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "hold" => {
+                    hold = match value {
+                        "" | "none" => None,
+                        _ => Some(
+                            value
+                                .chars()
+                                .next()
+                                .and_then(BiduleType::from_letter)
+                                .ok_or(format!("line {}: unknown hold piece", n))?,
+                        ),
+                    };
+                }
+                "board" => in_board = true,
+                other => return Err(format!("line {}: unknown key `{}`", n, other)),
+            }
+        }
+
+        let name = name.ok_or("puzzle without a name")?;
+        let goal = goal.ok_or(format!("{}: missing goal", name))?;
+        if pieces.is_empty() {
+            return Err(format!("{}: no pieces", name));
+        }
+        if rows.is_empty() {
+            return Err(format!("{}: no board", name));
+        }
+        puzzles.push(Puzzle {
+            name,
+            goal,
+            pieces,
+            hold,
+            rows,
+        });
+    }
+
+    Ok(puzzles)
+}
+
+fn parse_goal(value: &str) -> Option<PuzzleGoal> {
+    let mut words = value.split_whitespace();
+    let goal = match words.next()? {
+        "lines" => PuzzleGoal::ClearLines(words.next()?.parse().ok()?),
+        "perfect-clear" => PuzzleGoal::PerfectClear,
+        "tspin-double" => PuzzleGoal::TSpinDouble,
+        "survive" => PuzzleGoal::Survive(words.next()?.parse().ok()?),
+        _ => return None,
+    };
+    Some(goal)
+}
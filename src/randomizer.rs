@@ -0,0 +1,241 @@
+use crate::bidule::BiduleType;
+use fastrand::Rng;
+
+/// Decides the piece order. Randomness comes from the caller so a
+/// `GameSeed` stream fully determines the sequence.
+pub trait Randomizer {
+    /// The next piece, or `None` once a finite sequence has run out.
+    fn next(&mut self, rng: &mut Rng) -> Option<BiduleType>;
+}
+
+/// Selectable randomizer, stored in `Rules`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    Memoryless,
+    NesReroll,
+    TgmHistory,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 5] = [
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::Memoryless,
+        RandomizerKind::NesReroll,
+        RandomizerKind::TgmHistory,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-BAG",
+            RandomizerKind::FourteenBag => "14-BAG",
+            RandomizerKind::Memoryless => "RANDOM",
+            RandomizerKind::NesReroll => "NES",
+            RandomizerKind::TgmHistory => "TGM",
+        }
+    }
+
+    /// A randomizer drawing from `kinds` (see `PieceSet`).
+    pub fn build(self, kinds: &'static [BiduleType]) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(Bag::new(kinds, 1)),
+            RandomizerKind::FourteenBag => Box::new(Bag::new(kinds, 2)),
+            RandomizerKind::Memoryless => Box::new(Memoryless { kinds }),
+            RandomizerKind::NesReroll => Box::new(NesReroll { kinds, last: None }),
+            RandomizerKind::TgmHistory => Box::new(TgmHistory::new(kinds)),
+        }
+    }
+}
+
+/// Shuffled bag holding `copies` of each piece (1 = guideline 7-bag).
+pub struct Bag {
+    kinds: &'static [BiduleType],
+    copies: usize,
+    pieces: Vec<BiduleType>,
+}
+
+impl Bag {
+    pub fn new(kinds: &'static [BiduleType], copies: usize) -> Self {
+        Self {
+            kinds,
+            copies,
+            pieces: Vec::new(),
+        }
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut Rng) -> Option<BiduleType> {
+        if self.pieces.is_empty() {
+            for _ in 0..self.copies {
+                self.pieces.extend_from_slice(self.kinds);
+            }
+            rng.shuffle(&mut self.pieces);
+        }
+        self.pieces.pop()
+    }
+}
+
+/// Every piece equally likely, no memory.
+pub struct Memoryless {
+    kinds: &'static [BiduleType],
+}
+
+impl Randomizer for Memoryless {
+    fn next(&mut self, rng: &mut Rng) -> Option<BiduleType> {
+        Some(self.kinds[rng.usize(..self.kinds.len())])
+    }
+}
+
+/// NES: roll one side per piece plus one; a repeat of the last piece or the extra side
+/// rerolls once over the pieces.
+pub struct NesReroll {
+    kinds: &'static [BiduleType],
+    last: Option<BiduleType>,
+}
+
+impl Randomizer for NesReroll {
+    fn next(&mut self, rng: &mut Rng) -> Option<BiduleType> {
+        let roll = rng.usize(..self.kinds.len() + 1);
+        let kind = match self.kinds.get(roll) {
+            Some(&kind) if Some(kind) != self.last => kind,
+            _ => self.kinds[rng.usize(..self.kinds.len())],
+        };
+        self.last = Some(kind);
+        Some(kind)
+    }
+}
+
+/// TGM: avoid the last 4 pieces, trying up to 6 rolls. Never starts on S, Z or O
+/// (other piece sets start on anything).
+pub struct TgmHistory {
+    kinds: &'static [BiduleType],
+    history: [BiduleType; 4],
+    first: bool,
+}
+
+impl TgmHistory {
+    const ROLLS: usize = 6;
+    const OPENERS: [BiduleType; 4] = [BiduleType::I, BiduleType::T, BiduleType::J, BiduleType::L];
+
+    pub fn new(kinds: &'static [BiduleType]) -> Self {
+        Self {
+            kinds,
+            history: [BiduleType::Z, BiduleType::S, BiduleType::S, BiduleType::Z],
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmHistory {
+    fn next(&mut self, rng: &mut Rng) -> Option<BiduleType> {
+        let kind = if self.first {
+            self.first = false;
+            let openers: Vec<BiduleType> = Self::OPENERS
+                .into_iter()
+                .filter(|k| self.kinds.contains(k))
+                .collect();
+            let pool = if openers.is_empty() {
+                self.kinds
+            } else {
+                &openers
+            };
+            pool[rng.usize(..pool.len())]
+        } else {
+            let mut kind = self.kinds[rng.usize(..self.kinds.len())];
+            for _ in 1..Self::ROLLS {
+                if !self.history.contains(&kind) {
+                    break;
+                }
+                kind = self.kinds[rng.usize(..self.kinds.len())];
+            }
+            kind
+        };
+
+        self.history.rotate_right(1);
+        self.history[0] = kind;
+        Some(kind)
+    }
+}
+
+/// A fixed, finite order, as given by a puzzle.
+pub struct Sequence {
+    pieces: std::vec::IntoIter<BiduleType>,
+}
+
+impl Sequence {
+    pub fn new(pieces: Vec<BiduleType>) -> Self {
+        Self {
+            pieces: pieces.into_iter(),
+        }
+    }
+}
+
+impl Randomizer for Sequence {
+    fn next(&mut self, _rng: &mut Rng) -> Option<BiduleType> {
+        self.pieces.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bidule::PieceSet;
+
+    fn draw(randomizer: &mut dyn Randomizer, count: usize) -> Vec<BiduleType> {
+        let mut rng = Rng::with_seed(7);
+        (0..count)
+            .map(|_| randomizer.next(&mut rng).unwrap())
+            .collect()
+    }
+
+    /// Each bag's worth of pieces holds every kind exactly `copies` times.
+    fn assert_bags(kinds: &'static [BiduleType], copies: usize) {
+        let size = kinds.len() * copies;
+        let pieces = draw(&mut Bag::new(kinds, copies), size * 20);
+        for bag in pieces.chunks(size) {
+            for &kind in kinds {
+                assert_eq!(
+                    bag.iter().filter(|&&k| k == kind).count(),
+                    copies,
+                    "{:?}",
+                    bag
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn seven_bag_deals_permutations() {
+        assert_bags(PieceSet::Tetromino.kinds(), 1);
+    }
+
+    #[test]
+    fn fourteen_bag_deals_two_of_each() {
+        assert_bags(PieceSet::Tetromino.kinds(), 2);
+    }
+
+    #[test]
+    fn pentomino_bag_deals_every_pentomino() {
+        assert_bags(PieceSet::Pentomino.kinds(), 1);
+    }
+
+    #[test]
+    fn tgm_never_starts_on_s_z_or_o() {
+        for seed in 0..200 {
+            let first = TgmHistory::new(PieceSet::Tetromino.kinds())
+                .next(&mut Rng::with_seed(seed))
+                .unwrap();
+            assert!(![BiduleType::S, BiduleType::Z, BiduleType::O].contains(&first));
+        }
+    }
+
+    #[test]
+    fn sequence_runs_out() {
+        let mut sequence = Sequence::new(vec![BiduleType::T, BiduleType::I]);
+        assert_eq!(draw(&mut sequence, 2), [BiduleType::T, BiduleType::I]);
+        assert_eq!(sequence.next(&mut Rng::new()), None);
+    }
+}
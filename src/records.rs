@@ -0,0 +1,81 @@
+use std::fs;
+
+const RECORDS_FILE: &str = "records.txt";
+
+/// Personal bests, kept as `key=value` lines in a small text file next to the game.
+#[derive(Default)]
+pub struct Records {
+    /// Best 40-line time, in seconds
+    pub sprint: Option<f64>,
+    /// Best Ultra score
+    pub ultra: Option<i32>,
+    /// Best Dig time, in seconds
+    pub dig: Option<f64>,
+}
+
+impl Records {
+    /// Reads the records file; a missing or unreadable file means no records yet.
+    pub fn load() -> Self {
+        let mut records = Records::default();
+        let Ok(text) = fs::read_to_string(RECORDS_FILE) else {
+            return records;
+        };
+        for line in text.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "sprint" => records.sprint = value.trim().parse().ok(),
+                    "ultra" => records.ultra = value.trim().parse().ok(),
+                    "dig" => records.dig = value.trim().parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+        records
+    }
+
+    fn save(&self) {
+        let mut text = String::new();
+        if let Some(time) = self.sprint {
+            text.push_str(&format!("sprint={}\n", time));
+        }
+        if let Some(score) = self.ultra {
+            text.push_str(&format!("ultra={}\n", score));
+        }
+        if let Some(time) = self.dig {
+            text.push_str(&format!("dig={}\n", time));
+        }
+        if let Err(e) = fs::write(RECORDS_FILE, text) {
+            println!("Failed to save records: {:?}", e);
+        }
+    }
+
+    /// Records a finished sprint. Returns true if it is a new personal best.
+    pub fn submit_sprint(&mut self, time: f64) -> bool {
+        if self.sprint.is_some_and(|best| best <= time) {
+            return false;
+        }
+        self.sprint = Some(time);
+        self.save();
+        true
+    }
+
+    /// Records a finished dig. Returns true if it is a new personal best.
+    pub fn submit_dig(&mut self, time: f64) -> bool {
+        if self.dig.is_some_and(|best| best <= time) {
+            return false;
+        }
+        self.dig = Some(time);
+        self.save();
+        true
+    }
+
+    /// Records a finished Ultra. Returns true if it is a new personal best.
+    pub fn submit_ultra(&mut self, score: i32) -> bool {
+        if self.ultra.is_some_and(|best| best >= score) {
+            return false;
+        }
+        self.ultra = Some(score);
+        self.save();
+        true
+    }
+}
@@ -0,0 +1,174 @@
+/// Spin recognized when a T piece locks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full,
+}
+
+/// What earned a batch of points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScoreSource {
+    /// Line clears and T-spins, including spins that clear nothing
+    LineClear,
+    /// The extra half of a back-to-back difficult clear
+    BackToBack,
+    Combo,
+    PerfectClear,
+    SoftDrop,
+    HardDrop,
+    /// Clears set off by cascade gravity after the lock's own clear
+    Chain,
+}
+
+impl ScoreSource {
+    pub const ALL: [ScoreSource; 7] = [
+        ScoreSource::LineClear,
+        ScoreSource::BackToBack,
+        ScoreSource::Combo,
+        ScoreSource::PerfectClear,
+        ScoreSource::SoftDrop,
+        ScoreSource::HardDrop,
+        ScoreSource::Chain,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoreSource::LineClear => "LINE CLEARS",
+            ScoreSource::BackToBack => "BACK-TO-BACK",
+            ScoreSource::Combo => "COMBOS",
+            ScoreSource::PerfectClear => "PERFECT CLEARS",
+            ScoreSource::SoftDrop => "SOFT DROP",
+            ScoreSource::HardDrop => "HARD DROP",
+            ScoreSource::Chain => "CHAINS",
+        }
+    }
+}
+
+/// A multiplier from an active bonus. It scales one event's gain, never the running total.
+#[derive(Clone, Copy, Debug)]
+pub struct ScoreModifier {
+    pub name: &'static str,
+    pub factor: f32,
+}
+
+/// One scoring action: its base points, the modifiers applied, and what it added to the score.
+#[derive(Clone, Debug)]
+pub struct ScoreEvent {
+    pub source: ScoreSource,
+    pub base: i32,
+    pub modifiers: Vec<ScoreModifier>,
+    pub gain: i32,
+    /// Seconds of play when it was scored
+    pub time: f64,
+}
+
+impl ScoreEvent {
+    pub fn new(source: ScoreSource, base: i32, modifiers: Vec<ScoreModifier>, time: f64) -> Self {
+        let factor: f32 = modifiers.iter().map(|m| m.factor).product();
+        Self {
+            source,
+            base,
+            gain: (base as f32 * factor).round() as i32,
+            modifiers,
+            time,
+        }
+    }
+}
+
+/// Base points a game earned from `source`.
+pub fn base_total(log: &[ScoreEvent], source: ScoreSource) -> i32 {
+    log.iter().filter(|e| e.source == source).map(|e| e.base).sum()
+}
+
+/// Points a game earned on top of the base from bonus modifiers.
+pub fn modifier_total(log: &[ScoreEvent]) -> i32 {
+    log.iter().map(|e| e.gain - e.base).sum()
+}
+
+/// Points per cell travelled, as in the guideline.
+pub const SOFT_DROP_POINTS: i32 = 1;
+pub const HARD_DROP_POINTS: i32 = 2;
+
+/// Guideline base points for a lock, before the level multiplier.
+pub fn line_clear_points(lines: usize, tspin: TSpin) -> i32 {
+    match tspin {
+        TSpin::None => match lines {
+            1 => 100,
+            2 => 300,
+            3 => 500,
+            4 => 800,
+            // Only a pentomino I clears five
+            5 => 1200,
+            _ => 0,
+        },
+        TSpin::Mini => match lines {
+            0 => 100,
+            1 => 200,
+            _ => 400,
+        },
+        TSpin::Full => match lines {
+            0 => 400,
+            1 => 800,
+            2 => 1200,
+            _ => 1600,
+        },
+    }
+}
+
+/// "Difficult" clears keep a back-to-back chain alive: Tetrises (or bigger) and T-spins that clear lines.
+pub fn is_difficult(lines: usize, tspin: TSpin) -> bool {
+    lines >= 4 || (lines > 0 && tspin != TSpin::None)
+}
+
+/// Back-to-back difficult clears earn 1.5x their base points.
+pub fn back_to_back_points(points: i32) -> i32 {
+    points * 3 / 2
+}
+
+/// Base points for the `chain`-th clear of a cascade, counting the lock's own clear as the first.
+pub fn chain_points(lines: usize, chain: u32) -> i32 {
+    line_clear_points(lines, TSpin::None) * chain as i32
+}
+
+/// Extra base points for the n-th consecutive clearing lock (combo 0 is the first clear).
+pub fn combo_points(combo: i32) -> i32 {
+    50 * combo.max(0)
+}
+
+/// Garbage lines a clear sends, after the guideline attack table.
+/// Without an opponent they only cancel incoming garbage.
+pub fn attack_lines(lines: usize, tspin: TSpin, back_to_back: bool, combo: i32, perfect_clear: bool) -> usize {
+    const COMBO_ATTACK: [usize; 12] = [0, 0, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5];
+
+    let mut attack = match tspin {
+        TSpin::None => match lines {
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 => 5,
+            _ => 0,
+        },
+        TSpin::Mini => lines.saturating_sub(1),
+        TSpin::Full => lines * 2,
+    };
+    if back_to_back {
+        attack += 1;
+    }
+    attack += COMBO_ATTACK[(combo.max(0) as usize).min(COMBO_ATTACK.len() - 1)];
+    if perfect_clear {
+        attack += 10;
+    }
+    attack
+}
+
+/// Bonus base points for emptying the board, by the size of the clear that did it.
+pub fn perfect_clear_points(lines: usize, back_to_back: bool) -> i32 {
+    match lines {
+        1 => 800,
+        2 => 1200,
+        3 => 1800,
+        _ if back_to_back => 3200,
+        _ => 2000,
+    }
+}
@@ -0,0 +1,40 @@
+use fastrand::Rng;
+
+/// Owns every random stream of a game, all derived from one number.
+/// Streams are independent so that, e.g., drawing a bonus offer never
+/// shifts the piece order.
+pub struct GameSeed {
+    pub value: u64,
+    /// Piece order (randomizer)
+    pub pieces: Rng,
+    /// Bonus offers and bonus side effects (Volatile Grid explosions)
+    pub bonuses: Rng,
+    /// Cosmetic per-block data (jelly bubble seeds)
+    pub cosmetic: Rng,
+    /// Garbage hole columns
+    pub garbage: Rng,
+}
+
+impl GameSeed {
+    pub fn new(value: u64) -> Self {
+        Self {
+            value,
+            pieces: Rng::with_seed(derive(value, 1)),
+            bonuses: Rng::with_seed(derive(value, 2)),
+            cosmetic: Rng::with_seed(derive(value, 3)),
+            garbage: Rng::with_seed(derive(value, 4)),
+        }
+    }
+
+    pub fn random() -> Self {
+        Self::new(fastrand::u64(..))
+    }
+}
+
+/// SplitMix64 step, so neighbouring seeds and streams don't correlate.
+fn derive(value: u64, stream: u64) -> u64 {
+    let mut z = value.wrapping_add(stream.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,87 @@
+use crate::bidule::BiduleType;
+
+/// Which set of kick tests a piece uses. Pentominoes borrow the tetromino tables:
+/// the long I for the 5-long I, the JLSTZ one for everything else.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KickTable {
+    /// Does not turn (O)
+    Fixed,
+    /// The I piece table
+    Long,
+    /// The JLSTZ table
+    Standard,
+}
+
+// Super Rotation System wall kicks.
+// Offsets are (x, y) with y pointing UP, exactly as printed in the guideline tables.
+// The grid has y pointing down, so callers must subtract `y` when applying a kick.
+
+// Quarter turns. Rows: 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L
+const JLSTZ_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+];
+
+const I_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+];
+
+// 180° turns are not part of the original SRS; this is the widely used SRS+ table.
+// Rows: 0->2, 2->0, R->L, L->R
+const HALF_KICKS: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+const NO_KICK: [(i32, i32); 1] = [(0, 0)];
+
+/// Kick tests to try, in order, when turning `kind` from one rotation state to another.
+pub fn kicks(kind: BiduleType, from: usize, to: usize) -> &'static [(i32, i32)] {
+    if kind.kicks() == KickTable::Fixed {
+        return &NO_KICK;
+    }
+
+    let half_row = match (from, to) {
+        (0, 2) => Some(0),
+        (2, 0) => Some(1),
+        (1, 3) => Some(2),
+        (3, 1) => Some(3),
+        _ => None,
+    };
+    if let Some(row) = half_row {
+        return &HALF_KICKS[row];
+    }
+
+    let table = match kind.kicks() {
+        KickTable::Long => &I_KICKS,
+        _ => &JLSTZ_KICKS,
+    };
+
+    let row = match (from, to) {
+        (0, 1) => 0,
+        (1, 0) => 1,
+        (1, 2) => 2,
+        (2, 1) => 3,
+        (2, 3) => 4,
+        (3, 2) => 5,
+        (3, 0) => 6,
+        (0, 3) => 7,
+        _ => return &NO_KICK,
+    };
+    &table[row]
+}
@@ -2,6 +2,7 @@ use macroquad::prelude::*;
 use crate::constants::*;
 use crate::rect_utils::draw_rounded_rect;
 use crate::game::Game;
+use crate::mode::{ultra_rank, GameMode};
 
 /// Draws a "Jelly Frame" UI Panel with premium effects
 pub fn draw_panel(
@@ -158,14 +159,38 @@ pub fn draw_start_screen(game: &Game) {
     }
 
     // --- Menu Options ---
-    let options = vec![
-        "START GAME", 
+    let seed_label = if game.seed_input.is_empty() {
+        "SEED: RANDOM".to_string()
+    } else {
+        format!("SEED: {}", game.seed_input)
+    };
+    let mode_label = format!("MODE: < {} >", game.mode.name());
+    let randomizer_label = format!("PIECES: < {} >", game.randomizer.name());
+    let (board_w, board_h) = crate::game::BOARD_PRESETS[game.board_preset];
+    let board_label = format!("BOARD: < {}x{} >", board_w, board_h);
+    let next_label = format!("NEXT PIECES: < {} >", game.next_queue);
+    let cascade_label = format!("CASCADE: < {} >", if game.cascade { "ON" } else { "OFF" });
+    let gravity_label = format!("GRAVITY: < {} >", game.gravity.name());
+    let garbage_label = format!(
+        "GARBAGE: < {} >",
+        crate::game::GARBAGE_PRESETS[game.garbage_preset].0
+    );
+    let options = [
+        "START GAME",
+        &mode_label,
+        &seed_label,
+        &randomizer_label,
+        &board_label,
+        &garbage_label,
+        &gravity_label,
+        &next_label,
+        &cascade_label,
         if game.is_music_playing { "OPTIONS: MUSIC ON" } else { "OPTIONS: MUSIC OFF" },
         "EXIT"
     ];
     
-    let start_y = screen_h * 0.5;
-    let spacing = 60.0;
+    let start_y = screen_h * 0.42;
+    let spacing = 50.0;
 
     for (i, opt) in options.iter().enumerate() {
         let is_selected = i == game.menu_selection;
@@ -200,7 +225,7 @@ pub fn draw_start_screen(game: &Game) {
     }
 
     // Footer
-    let footer = "Use ARROW KEYS and ENTER";
+    let footer = "Use ARROW KEYS and ENTER - type digits on SEED to replay a game";
     draw_text(footer, 20.0, screen_h - 20.0, 20.0, GRAY);
 }
 
@@ -225,19 +250,67 @@ pub fn draw_game_over(game: &Game) {
             ..Default::default()
         });
         
-        let sub = format!("Score: {}", game.score);
+        let cause = if game.engine.out_of_pieces {
+            Some("OUT OF PIECES")
+        } else {
+            game.engine.top_out.map(|cause| cause.name())
+        };
+        if let Some(cause) = cause {
+            let dim_c = measure_text(cause, Some(f), 35, 1.0);
+            draw_text_ex(cause, (screen_w - dim_c.width) / 2.0, y + 70.0, TextParams {
+                font: Some(f),
+                font_size: 35,
+                color: ORANGE,
+                ..Default::default()
+            });
+        }
+
+        let sub = format!("Score: {}", game.engine.score);
         let dim_s = measure_text(&sub, Some(f), 40, 1.0);
         
-        draw_text_ex(&sub, (screen_w - dim_s.width) / 2.0, y + 80.0, TextParams {
+        draw_text_ex(&sub, (screen_w - dim_s.width) / 2.0, y + 130.0, TextParams {
             font: Some(f),
             font_size: 40,
             color: WHITE,
             ..Default::default()
         });
 
+        if let Some(limit) = game.engine.rules.mode.time_limit() {
+            let ultra = format!(
+                "Time left: {}  -  Rank {}",
+                format_time(limit - game.engine.elapsed),
+                ultra_rank(game.engine.score)
+            );
+            let dim_u = measure_text(&ultra, Some(f), 30, 1.0);
+            draw_text_ex(&ultra, (screen_w - dim_u.width) / 2.0, y - 110.0, TextParams {
+                font: Some(f),
+                font_size: 30,
+                color: GOLD,
+                ..Default::default()
+            });
+        }
+
+        let breakdown = "Press B for the score breakdown";
+        let dim_b = measure_text(breakdown, Some(f), 25, 1.0);
+        draw_text_ex(breakdown, (screen_w - dim_b.width) / 2.0, y + 175.0, TextParams {
+            font: Some(f),
+            font_size: 25,
+            color: LIGHTGRAY,
+            ..Default::default()
+        });
+
+        let seed = format!("Seed: {}", game.engine.seed.value);
+        let dim_seed = measure_text(&seed, Some(f), 30, 1.0);
+        draw_text_ex(&seed, (screen_w - dim_seed.width) / 2.0, y + 220.0, TextParams {
+            font: Some(f),
+            font_size: 30,
+            color: LIGHTGRAY,
+            ..Default::default()
+        });
+
         let restart = "Press R to Restart";
         let dim_r = measure_text(restart, Some(f), 30, 1.0);
-        draw_text_ex(restart, (screen_w - dim_r.width) / 2.0, y + 140.0, TextParams {
+        draw_text_ex(restart, (screen_w - dim_r.width) / 2.0, y + 270.0, TextParams {
             font: Some(f),
             font_size: 30,
             color: GRAY,
@@ -246,6 +319,253 @@ pub fn draw_game_over(game: &Game) {
     }
 }
 
+/// `m:ss.mmm`, for mode timers.
+pub fn format_time(secs: f64) -> String {
+    let millis = (secs * 1000.0).round() as u64;
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+/// End of a mode with a goal: the final time or score, its rank and the personal best.
+pub fn draw_results(game: &Game) {
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+
+    draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.85));
+
+    let Some(f) = game.font.as_ref() else {
+        return;
+    };
+    let centered = |text: &str, y: f32, size: u16, color: Color| {
+        let dim = measure_text(text, Some(f), size, 1.0);
+        draw_text_ex(text, (screen_w - dim.width) / 2.0, y, TextParams {
+            font: Some(f),
+            font_size: size,
+            color,
+            ..Default::default()
+        });
+    };
+
+    let engine = &game.engine;
+    let y = screen_h * 0.3;
+    centered(&format!("{} COMPLETE", engine.rules.mode.name()), y, 80, GOLD);
+
+    let mut detail_y = y + 250.0;
+    match engine.rules.mode {
+        GameMode::Puzzle => {
+            if let Some(puzzle) = &engine.rules.puzzle {
+                centered(&puzzle.name, y + 110.0, 70, WHITE);
+                centered(&puzzle.goal.describe(), y + 180.0, 35, LIGHTGRAY);
+            }
+        }
+        GameMode::Ultra => {
+            centered(&format!("{}", engine.score), y + 110.0, 90, WHITE);
+            if game.new_best {
+                centered("NEW PERSONAL BEST!", y + 180.0, 40, ORANGE);
+            } else if !engine.rules.is_standard() {
+                centered("Custom rules: not recorded", y + 180.0, 35, LIGHTGRAY);
+            } else if let Some(best) = game.records.ultra {
+                centered(&format!("Personal best: {}", best), y + 180.0, 35, LIGHTGRAY);
+            }
+            centered(&format!("RANK {}", ultra_rank(engine.score)), detail_y, 60, GOLD);
+            detail_y += 50.0;
+        }
+        mode => {
+            // Races against the clock
+            centered(&format_time(engine.elapsed), y + 110.0, 90, WHITE);
+            let best = if mode == GameMode::Dig {
+                game.records.dig
+            } else {
+                game.records.sprint
+            };
+            if game.new_best {
+                centered("NEW PERSONAL BEST!", y + 180.0, 40, ORANGE);
+            } else if !engine.rules.is_standard() {
+                centered("Custom rules: not recorded", y + 180.0, 35, LIGHTGRAY);
+            } else if let Some(best) = best {
+                centered(&format!("Personal best: {}", format_time(best)), y + 180.0, 35, LIGHTGRAY);
+            }
+            if mode == GameMode::Sprint {
+                for (i, split) in engine.splits.iter().enumerate() {
+                    let line = format!("{}L   {}", (i + 1) * 10, format_time(*split));
+                    centered(&line, detail_y, 30, LIGHTGRAY);
+                    detail_y += 40.0;
+                }
+            }
+        }
+    }
+
+    centered(
+        &format!("Pieces: {}  -  Seed: {}", engine.pieces_locked, engine.seed.value),
+        detail_y + 30.0,
+        25,
+        GRAY,
+    );
+    centered("Press R to Restart", detail_y + 80.0, 30, GRAY);
+}
+
+/// List of puzzles with their goals, picked with the arrow keys.
+pub fn draw_puzzle_select(game: &Game) {
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+
+    draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.8));
+
+    let Some(f) = game.font.as_ref() else {
+        return;
+    };
+
+    let title = "CHOOSE A PUZZLE";
+    let dim = measure_text(title, Some(f), 80, 1.0);
+    draw_text_ex(title, (screen_w - dim.width) / 2.0, screen_h * 0.2, TextParams {
+        font: Some(f),
+        font_size: 80,
+        color: WHITE,
+        ..Default::default()
+    });
+
+    let start_y = screen_h * 0.32;
+    for (i, puzzle) in game.puzzles.iter().enumerate() {
+        let is_selected = i == game.puzzle_selection;
+        let color = if is_selected { GOLD } else { LIGHTGRAY };
+        let size = if is_selected { 45 } else { 38 };
+        let y = start_y + i as f32 * 80.0;
+
+        let dim = measure_text(&puzzle.name, Some(f), size, 1.0);
+        let x = (screen_w - dim.width) / 2.0;
+        if is_selected {
+            draw_text_ex(">", x - 30.0, y, TextParams {
+                font: Some(f),
+                font_size: size,
+                color: GOLD,
+                ..Default::default()
+            });
+        }
+        draw_text_ex(&puzzle.name, x, y, TextParams {
+            font: Some(f),
+            font_size: size,
+            color,
+            ..Default::default()
+        });
+
+        let goal = format!("{}  -  {} pieces", puzzle.goal.describe(), puzzle.pieces.len());
+        let dim_g = measure_text(&goal, Some(f), 22, 1.0);
+        draw_text_ex(&goal, (screen_w - dim_g.width) / 2.0, y + 28.0, TextParams {
+            font: Some(f),
+            font_size: 22,
+            color: GRAY,
+            ..Default::default()
+        });
+    }
+
+    let footer = "ENTER to play - ESC to go back";
+    draw_text(footer, 20.0, screen_h - 20.0, 20.0, GRAY);
+}
+
+/// Dims the frozen game until it is resumed.
+pub fn draw_pause(game: &Game) {
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+
+    draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.6));
+
+    if let Some(f) = game.font.as_ref() {
+        let text = "PAUSED";
+        let dim = measure_text(text, Some(f), 100, 1.0);
+        draw_text_ex(text, (screen_w - dim.width) / 2.0, screen_h / 2.0, TextParams {
+            font: Some(f),
+            font_size: 100,
+            color: WHITE,
+            ..Default::default()
+        });
+
+        let hint = "Press P to resume";
+        let dim_h = measure_text(hint, Some(f), 30, 1.0);
+        draw_text_ex(hint, (screen_w - dim_h.width) / 2.0, screen_h / 2.0 + 70.0, TextParams {
+            font: Some(f),
+            font_size: 30,
+            color: GRAY,
+            ..Default::default()
+        });
+    }
+}
+
+/// Where the score came from: base points per source, bonus modifiers, and the latest events.
+pub fn draw_score_breakdown(game: &Game) {
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+
+    draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.9));
+
+    let Some(f) = game.font.as_ref() else {
+        return;
+    };
+    let log = &game.engine.score_log;
+    let left = screen_w / 2.0 - 400.0;
+    let right = screen_w / 2.0 + 400.0;
+
+    let title = "SCORE BREAKDOWN";
+    let dim = measure_text(title, Some(f), 70, 1.0);
+    draw_text_ex(title, (screen_w - dim.width) / 2.0, 150.0, TextParams {
+        font: Some(f),
+        font_size: 70,
+        color: GOLD,
+        ..Default::default()
+    });
+
+    // One row per source, value right-aligned
+    let row = |label: &str, value: String, y: f32, color: Color| {
+        draw_text_ex(label, left, y, TextParams {
+            font: Some(f),
+            font_size: 35,
+            color,
+            ..Default::default()
+        });
+        let dim_v = measure_text(&value, Some(f), 35, 1.0);
+        draw_text_ex(&value, right - dim_v.width, y, TextParams {
+            font: Some(f),
+            font_size: 35,
+            color,
+            ..Default::default()
+        });
+    };
+
+    let mut y = 240.0;
+    for source in crate::scoring::ScoreSource::ALL {
+        let total = crate::scoring::base_total(log, source);
+        row(source.name(), format!("{}", total), y, WHITE);
+        y += 45.0;
+    }
+    row("BONUS MODIFIERS", format!("+{}", crate::scoring::modifier_total(log)), y, PURPLE);
+    y += 60.0;
+    row("TOTAL", format!("{}", game.engine.score), y, GOLD);
+    y += 70.0;
+
+    // Latest events, newest first
+    for event in log.iter().rev().take(8) {
+        let mut text = format!("{}  {} {}", format_time(event.time), event.source.name(), event.base);
+        for m in &event.modifiers {
+            text.push_str(&format!("  x{:.1} {}", m.factor, m.name));
+        }
+        text.push_str(&format!("  = +{}", event.gain));
+        draw_text_ex(&text, left, y, TextParams {
+            font: Some(f),
+            font_size: 25,
+            color: LIGHTGRAY,
+            ..Default::default()
+        });
+        y += 32.0;
+    }
+
+    let back = "Press B to go back - R to Restart";
+    let dim_b = measure_text(back, Some(f), 30, 1.0);
+    draw_text_ex(back, (screen_w - dim_b.width) / 2.0, screen_h - 60.0, TextParams {
+        font: Some(f),
+        font_size: 30,
+        color: GRAY,
+        ..Default::default()
+    });
+}
+
 /// Helper for basic styled text
 pub fn draw_text_styled(text: &str, x: f32, y: f32, size: f32, color: Color) {
     draw_text(text, x, y + 2.0, size, BLACK); // Shadow
@@ -289,7 +609,7 @@ pub fn draw_bonus_selection(game: &Game) {
     let font_ref = game.font.as_ref();
     let time = get_time();
 
-    for (i, bonus) in game.bonus_options.iter().enumerate() {
+    for (i, bonus) in game.engine.bonus_options.iter().enumerate() {
         let is_selected = i == game.bonus_selection_idx;
         
         // Hover/Selection effect